@@ -0,0 +1,297 @@
+use {
+    crate::*,
+    itertools::Itertools,
+    serde::Serialize,
+    std::{
+        collections::HashMap,
+        fmt,
+        hash::Hash,
+        io,
+        path::PathBuf,
+    },
+};
+
+/// Minimal number of days for comparing a day to the same
+/// days of the other weeks
+const SEASONAL_MIN_DAYS: usize = 21;
+
+/// Maximal number of days for which hours are analyzed
+/// rather than days
+const HOURLY_MAX_DAYS: usize = 3;
+
+/// Neither the count nor the baseline of a bucket reach this
+/// number, the bucket is too small to be significant
+const MIN_COUNT: f64 = 10.0;
+
+/// A time slot, either a day or an hour of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub date: Date,
+    pub hour: Option<u8>,
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hour {
+            Some(hour) => write!(f, "{} {:0>2}h", self.date, hour),
+            None => write!(f, "{}", self.date),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    Spike,
+    Drop,
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spike => write!(f, "spike"),
+            Self::Drop => write!(f, "drop"),
+        }
+    }
+}
+
+/// A bucket in which the hits of a group deviate significantly
+/// from the usual ones
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub dimension: &'static str,
+    pub value: String,
+    pub bucket: Bucket,
+    pub count: u64,
+    pub baseline: f64,
+    pub score: f64,
+}
+
+impl Anomaly {
+    pub fn kind(&self) -> AnomalyKind {
+        if self.score > 0.0 {
+            AnomalyKind::Spike
+        } else {
+            AnomalyKind::Drop
+        }
+    }
+    /// The count relative to the baseline, eg `+250%`
+    pub fn magnitude(&self) -> String {
+        if self.baseline < 0.5 {
+            "new".to_owned()
+        } else {
+            format!("{:+.0}%", 100.0 * (self.count as f64 - self.baseline) / self.baseline)
+        }
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+/// Compute the baseline of a series and the scale of its usual
+/// deviations, based on the median and the median absolute deviation,
+/// so that a few outliers don't change them
+fn robust_baseline(values: &mut [f64]) -> (f64, f64) {
+    let med = median(values);
+    let mut deviations: Vec<f64> = values.iter().map(|r| (r - med).abs()).collect();
+    let mad = median(&mut deviations);
+    // when most values are equal, the MAD is 0, so we fall back
+    // to the deviation expected from a Poisson process
+    let scale = (1.4826 * mad).max(med.sqrt()).max(1.0);
+    (med, scale)
+}
+
+/// Detects the days (or hours when the period is short) where the
+/// hits of a group deviate from the ones of the other days
+pub struct AnomalyDetector<'b> {
+    base: &'b LogBase,
+    threshold: f64,
+    hourly: bool,
+    buckets: Vec<Bucket>,
+    /// the indexes of the buckets not excluded by date filters,
+    /// grouped by the ones which can be compared together
+    classes: Vec<Vec<usize>>,
+    /// the first and last not excluded buckets
+    ends: Option<(usize, usize)>,
+}
+
+impl<'b> AnomalyDetector<'b> {
+    pub fn new(base: &'b LogBase, threshold: f64) -> Self {
+        let day_count = base.dates
+            .iter()
+            .filter(|&&date| base.filterer.overlaps(date))
+            .count();
+        let hourly = day_count <= HOURLY_MAX_DAYS;
+        let seasonal = !hourly && day_count >= SEASONAL_MIN_DAYS;
+        let buckets: Vec<Bucket> = if hourly {
+            base.dates
+                .iter()
+                .flat_map(|&date| (0..24).map(move |hour| Bucket { date, hour: Some(hour) }))
+                .collect()
+        } else {
+            base.dates
+                .iter()
+                .map(|&date| Bucket { date, hour: None })
+                .collect()
+        };
+        let active: Vec<usize> = (0..buckets.len())
+            .filter(|&i| base.filterer.overlaps(buckets[i].date))
+            .collect();
+        let ends = active.first().zip(active.last()).map(|(&a, &b)| (a, b));
+        let classes = if seasonal {
+            // a day is compared to the same days of the other weeks
+            active
+                .into_iter()
+                .into_group_map_by(|&i| buckets[i].date.weekday())
+                .into_values()
+                .collect()
+        } else {
+            vec![active]
+        };
+        Self { base, threshold, hourly, buckets, classes, ends }
+    }
+    /// Tell whether the buckets are hours rather than days
+    pub fn is_hourly(&self) -> bool {
+        self.hourly
+    }
+    fn bucket_idx(&self, line: &LogLine) -> usize {
+        if self.hourly {
+            line.date_idx * 24 + line.time().hour as usize
+        } else {
+            line.date_idx
+        }
+    }
+    fn detect_in<K, F, G>(
+        &self,
+        dimension: &'static str,
+        filter: F,
+        grouper: G,
+        anomalies: &mut Vec<Anomaly>,
+    ) where
+        K: fmt::Display + Hash + Eq,
+        F: Fn(&LogLine) -> bool,
+        G: Fn(&'b LogLine) -> K,
+    {
+        let mut groups: HashMap<K, Vec<u64>> = HashMap::new();
+        for line in self.base.lines.iter().filter(|line| filter(line)) {
+            groups
+                .entry(grouper(line))
+                .or_insert_with(|| vec![0; self.buckets.len()])[self.bucket_idx(line)] += 1;
+        }
+        for (key, counts) in groups {
+            if counts.iter().all(|&c| (c as f64) < MIN_COUNT) {
+                continue;
+            }
+            for class in &self.classes {
+                if class.len() < 4 {
+                    continue;
+                }
+                let mut values: Vec<f64> = class.iter().map(|&i| counts[i] as f64).collect();
+                let (baseline, scale) = robust_baseline(&mut values);
+                for &i in class {
+                    let count = counts[i] as f64;
+                    let score = (count - baseline) / scale;
+                    if score.abs() < self.threshold || count.max(baseline) < MIN_COUNT {
+                        continue;
+                    }
+                    if score < 0.0 && matches!(self.ends, Some((a, b)) if i == a || i == b) {
+                        // the first and last buckets are usually incomplete
+                        continue;
+                    }
+                    anomalies.push(Anomaly {
+                        dimension,
+                        value: key.to_string(),
+                        bucket: self.buckets[i],
+                        count: counts[i],
+                        baseline,
+                        score,
+                    });
+                }
+            }
+        }
+    }
+    /// Return the anomalies, the most significant first
+    pub fn detect(&self, all_paths: bool) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        self.detect_in("all", |_| true, |_| "all hits", &mut anomalies);
+        self.detect_in(
+            "path",
+            |line| all_paths || !line.is_resource(),
+            |line| &line.path,
+            &mut anomalies,
+        );
+        self.detect_in("ip", |_| true, |line| &line.remote_addr, &mut anomalies);
+        self.detect_in(
+            "referer",
+            |line| line.referer.len() > 1,
+            |line| &line.referer,
+            &mut anomalies,
+        );
+        self.detect_in("status", |_| true, |line| line.status, &mut anomalies);
+        anomalies.sort_by(|a, b| b.score.abs().partial_cmp(&a.score.abs()).unwrap());
+        anomalies
+    }
+}
+
+/// An anomaly, as exported in JSON
+#[derive(Debug, Serialize)]
+struct AnomalyJson<'a> {
+    dimension: &'static str,
+    value: &'a str,
+    bucket: String,
+    kind: String,
+    count: u64,
+    baseline: f64,
+    score: f64,
+}
+
+/// Print the anomalies as a JSON array, for alerting tools
+pub fn print_anomalies_json(
+    paths: &[PathBuf],
+    args: &args::Args,
+) -> Result<(), RhitError> {
+    let base = time!("LogBase::new", LogBase::new(paths, args))?;
+    let detector = AnomalyDetector::new(&base, args.anomaly_threshold);
+    let anomalies = time!(detector.detect(args.all));
+    let anomalies: Vec<AnomalyJson> = anomalies
+        .iter()
+        .map(|anomaly| AnomalyJson {
+            dimension: anomaly.dimension,
+            value: &anomaly.value,
+            bucket: anomaly.bucket.to_string(),
+            kind: anomaly.kind().to_string(),
+            count: anomaly.count,
+            baseline: (anomaly.baseline * 10.0).round() / 10.0,
+            score: (anomaly.score * 100.0).round() / 100.0,
+        })
+        .collect();
+    serde_json::to_writer_pretty(io::stdout().lock(), &anomalies)?;
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod anomalies_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_robust_baseline() {
+        // the outlier doesn't move the baseline
+        let mut values = [10.0, 12.0, 11.0, 60.0, 9.0, 10.0, 13.0];
+        let (baseline, scale) = robust_baseline(&mut values);
+        assert_eq!(baseline, 11.0);
+        assert!((60.0 - baseline) / scale > 10.0);
+        assert!((12.0 - baseline) / scale < 1.0);
+        // a constant series doesn't make every change an anomaly
+        let mut values = [0.0, 0.0, 0.0, 0.0];
+        let (baseline, scale) = robust_baseline(&mut values);
+        assert!((2.0 - baseline) / scale < 3.5);
+    }
+}
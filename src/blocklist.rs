@@ -0,0 +1,173 @@
+use {
+    crate::*,
+    clap::ValueEnum,
+    std::{
+        collections::HashMap,
+        fs::File,
+        io::{self, BufWriter, Write},
+        net::IpAddr,
+        path::PathBuf,
+    },
+};
+
+/// The format of a blocklist of remote addresses
+#[derive(ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlocklistFormat {
+    /// One address per line
+    #[default]
+    Plain,
+    /// One address or block per line, contiguous addresses being merged
+    Cidr,
+    /// `deny` directives, to include in a nginx `server` or `http` block
+    NginxDeny,
+    /// Entries of a nginx `geo` block, eg `geo $blocked { default 0; include ...; }`
+    NginxGeoMap,
+    /// Commands for `ipset restore`
+    Ipset,
+    /// Fail2ban compatible `ban` lines, to feed `fail2ban-client`
+    Fail2ban,
+}
+
+/// What we know of a remote address
+#[derive(Default)]
+struct AddrStats {
+    hits: u64,
+    errors: u64,
+    first: i64, // timestamp in seconds
+    last: i64,
+}
+
+impl AddrStats {
+    fn error_ratio(&self) -> f32 {
+        self.errors as f32 / self.hits as f32
+    }
+    /// Average number of hits per minute, between the first and last ones
+    fn rate(&self) -> f32 {
+        let minutes = ((self.last - self.first) as f32 / 60.0).max(1.0);
+        self.hits as f32 / minutes
+    }
+}
+
+/// A consumer building the list of remote addresses of the
+/// accepted lines, with their stats
+#[derive(Default)]
+struct BlocklistBuilder {
+    addrs: HashMap<Box<str>, AddrStats>,
+}
+
+impl LineConsumer for BlocklistBuilder {
+    fn eat_line(
+        &mut self,
+        line: LogLine,
+        _raw_line: &str,
+        filtered_out: bool,
+    ) {
+        if filtered_out { return; }
+        let timestamp = line.date_time.timestamp();
+        let stats = self.addrs
+            .entry(line.remote_addr)
+            .or_insert_with(|| AddrStats {
+                first: timestamp,
+                ..Default::default()
+            });
+        stats.hits += 1;
+        if line.status >= 400 {
+            stats.errors += 1;
+        }
+        stats.last = timestamp;
+    }
+}
+
+fn write_blocklist<W: Write>(
+    mut w: W,
+    addrs: Vec<IpAddr>,
+    args: &args::Args,
+) -> io::Result<()> {
+    let set = &args.ipset_name;
+    match args.blocklist_format {
+        BlocklistFormat::Plain => {
+            for addr in addrs {
+                writeln!(w, "{}", addr)?;
+            }
+        }
+        BlocklistFormat::Cidr => {
+            let cidrs = addrs.into_iter().map(Cidr::single).collect();
+            for cidr in aggregate_cidrs(cidrs) {
+                writeln!(w, "{}", cidr)?;
+            }
+        }
+        BlocklistFormat::NginxDeny => {
+            writeln!(w, "# generated by rhit")?;
+            for addr in addrs {
+                writeln!(w, "deny {};", addr)?;
+            }
+        }
+        BlocklistFormat::NginxGeoMap => {
+            writeln!(w, "# generated by rhit, to include in a geo block")?;
+            for addr in addrs {
+                writeln!(w, "{} 1;", addr)?;
+            }
+        }
+        BlocklistFormat::Ipset => {
+            writeln!(w, "create {} hash:ip family inet -exist", set)?;
+            writeln!(w, "create {}-v6 hash:ip family inet6 -exist", set)?;
+            for addr in addrs {
+                match addr {
+                    IpAddr::V4(_) => writeln!(w, "add {} {} -exist", set, addr)?,
+                    IpAddr::V6(_) => writeln!(w, "add {}-v6 {} -exist", set, addr)?,
+                }
+            }
+        }
+        BlocklistFormat::Fail2ban => {
+            for addr in addrs {
+                writeln!(w, "set {} banip {}", set, addr)?;
+            }
+        }
+    }
+    w.flush()
+}
+
+/// Write the remote addresses of the accepted lines which pass the
+/// thresholds, in one of the blocklist formats, either in the
+/// standard output or in a file.
+///
+/// At least one threshold is required so that a blocklist doesn't
+/// end up containing every address, including the operator's own
+pub fn print_blocklist(
+    paths: &[PathBuf],
+    args: &args::Args,
+) -> Result<(), RhitError> {
+    if args.min_hits.is_none() && args.min_error_ratio.is_none() && args.min_rate.is_none() {
+        return Err(RhitError::NoBlocklistThreshold);
+    }
+    let mut builder = BlocklistBuilder::default();
+    let mut file_reader = FileReader::new(paths, args, &mut builder)?;
+    time!("reading files", file_reader.read_all_files())?;
+    let mut addrs: Vec<IpAddr> = builder.addrs
+        .iter()
+        .filter(|(_, stats)| {
+            args.min_hits.map_or(true, |min| stats.hits >= min)
+                && args.min_error_ratio.map_or(true, |min| stats.error_ratio() >= min)
+                && args.min_rate.map_or(true, |min| stats.rate() >= min)
+        })
+        .filter_map(|(addr, _)| match addr.parse() {
+            Ok(addr) => Some(addr),
+            Err(_) => {
+                debug!("not an IP address: {:?}", addr);
+                None
+            }
+        })
+        .collect();
+    addrs.sort();
+    match &args.blocklist_file {
+        Some(path) => {
+            let file = BufWriter::new(File::create(path)?);
+            write_blocklist(file, addrs, args)?;
+        }
+        None => {
+            write_blocklist(io::stdout().lock(), addrs, args)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use {
+    crate::*,
+    lazy_regex::regex::{self, RegexSet},
+    std::{
+        collections::HashMap,
+        path::Path,
+    },
+    thiserror::Error,
+};
+
+/// The embedded list of bot signatures
+static SIGNATURES: &str = include_str!("signatures.txt");
+
+/// Number of HEAD requests after which a remote address
+/// is considered to be a bot
+const HEAD_STORM_THRESHOLD: u32 = 50;
+
+#[derive(Debug, Error)]
+pub enum ParseBotSignatureError {
+    #[error("invalid bot signature line {0:?}")]
+    InvalidLine(String),
+    #[error("invalid bot signature {0:?}")]
+    InvalidRegex(#[from] regex::Error),
+}
+
+/// What we've seen of a remote address until now
+#[derive(Default)]
+struct AddrBehavior {
+    fetched_robots_txt: bool,
+    head_count: u32,
+}
+
+/// Classifies hits as human or bot ones.
+///
+/// The classification is based on a list of signatures searched in
+/// the user agent, and on hints about the past behavior of the
+/// remote address (fetching robots.txt, sending many HEAD requests).
+///
+/// As log lines are read in order, a remote address is only
+/// classified as bot on behavior after it has shown it.
+pub struct BotClassifier {
+    kinds: Vec<BotKind>,
+    set: RegexSet,
+    behaviors: HashMap<Box<str>, AddrBehavior>,
+    // the same user agents come again and again, so the signatures
+    // are searched only once per distinct user agent
+    signature_matches: HashMap<&'static str, Option<BotKind>>,
+}
+
+fn parse_signatures(
+    s: &'static str,
+    kinds: &mut Vec<BotKind>,
+    patterns: &mut Vec<String>,
+) -> Result<(), ParseBotSignatureError> {
+    for line in s.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (kind, name) = match line.split_once(char::is_whitespace) {
+            Some(("good", name)) => (BotKind::Good(name.trim()), name.trim()),
+            Some(("bot", name)) => (BotKind::Other(name.trim()), name.trim()),
+            _ => {
+                return Err(ParseBotSignatureError::InvalidLine(line.to_owned()));
+            }
+        };
+        kinds.push(kind);
+        patterns.push(format!("(?i){}", regex::escape(name)));
+    }
+    Ok(())
+}
+
+impl BotClassifier {
+    /// Build a classifier from the embedded signatures, and optionally
+    /// from the ones of a file (which are tested first)
+    pub fn new(signatures_file: Option<&Path>) -> Result<Self, RhitError> {
+        let mut kinds = Vec::new();
+        let mut patterns = Vec::new();
+        if let Some(path) = signatures_file {
+            let content = leak::read_leaked(path)?;
+            parse_signatures(content, &mut kinds, &mut patterns)?;
+        }
+        parse_signatures(SIGNATURES, &mut kinds, &mut patterns)?;
+        let set = RegexSet::new(patterns).map_err(ParseBotSignatureError::from)?;
+        Ok(Self {
+            kinds,
+            set,
+            behaviors: HashMap::new(),
+            signature_matches: HashMap::new(),
+        })
+    }
+    pub fn classify(&mut self, line: &LogLine) -> BotKind {
+        let behavior = match self.behaviors.get_mut(&line.remote_addr) {
+            Some(behavior) => behavior,
+            None => self.behaviors.entry(line.remote_addr.clone()).or_default(),
+        };
+        if &*line.path == "/robots.txt" {
+            behavior.fetched_robots_txt = true;
+        }
+        if line.method == Method::Head {
+            behavior.head_count += 1;
+        }
+        let ua = line.user_agent;
+        let signature_match = match self.signature_matches.get(ua) {
+            Some(&signature_match) => signature_match,
+            None => {
+                let signature_match = self.set
+                    .matches(ua)
+                    .iter()
+                    .next()
+                    .map(|idx| self.kinds[idx]);
+                self.signature_matches.insert(ua, signature_match);
+                signature_match
+            }
+        };
+        if let Some(kind) = signature_match {
+            return kind;
+        }
+        if ua.is_empty() || ua == "-" {
+            return BotKind::Other("no user agent");
+        }
+        if behavior.fetched_robots_txt {
+            return BotKind::Other("robots.txt fetcher");
+        }
+        if behavior.head_count >= HEAD_STORM_THRESHOLD {
+            return BotKind::Other("HEAD storm");
+        }
+        if line.referer.len() < 2 && !ua.starts_with("Mozilla/") {
+            // no referer and no browser: probably a script
+            return BotKind::Other("script");
+        }
+        BotKind::Human
+    }
+}
+
+#[cfg(test)]
+mod bot_classifier_tests {
+
+    use super::*;
+
+    static FIREFOX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:120.0) Gecko/20100101 Firefox/120.0";
+
+    #[test]
+    fn test_signatures() {
+        let mut bc = BotClassifier::new(None).unwrap();
+        let ll = LogLine::test("/").with_addr("1.2.3.4").with_user_agent(FIREFOX);
+        assert_eq!(bc.classify(&ll), BotKind::Human);
+        let ll = LogLine::test("/").with_addr("1.2.3.5").with_user_agent("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)");
+        assert_eq!(bc.classify(&ll), BotKind::Good("Googlebot"));
+        let ll = LogLine::test("/").with_addr("1.2.3.6").with_user_agent("Mozilla/5.0 (compatible; AhrefsBot/7.0; +http://ahrefs.com/robot/)");
+        assert_eq!(bc.classify(&ll), BotKind::Other("AhrefsBot"));
+        let ll = LogLine::test("/").with_addr("1.2.3.7").with_user_agent("curl/7.68.0");
+        assert_eq!(bc.classify(&ll), BotKind::Other("curl"));
+        let ll = LogLine::test("/").with_addr("1.2.3.8").with_user_agent("-");
+        assert_eq!(bc.classify(&ll), BotKind::Other("no user agent"));
+    }
+
+    #[test]
+    fn test_behavior() {
+        let mut bc = BotClassifier::new(None).unwrap();
+        let ll = LogLine::test("/").with_addr("1.2.3.4").with_referer("https://dystroy.org").with_user_agent(FIREFOX);
+        assert_eq!(bc.classify(&ll), BotKind::Human);
+        let ll = LogLine::test("/robots.txt").with_addr("1.2.3.4").with_user_agent(FIREFOX);
+        assert_eq!(bc.classify(&ll), BotKind::Other("robots.txt fetcher"));
+        let ll = LogLine::test("/").with_addr("1.2.3.4").with_referer("https://dystroy.org").with_user_agent(FIREFOX);
+        assert_eq!(bc.classify(&ll), BotKind::Other("robots.txt fetcher"));
+        for _ in 0..HEAD_STORM_THRESHOLD {
+            let ll = LogLine::test("/").with_addr("1.2.3.9").with_method("HEAD").with_referer("https://dystroy.org").with_user_agent(FIREFOX);
+            bc.classify(&ll);
+        }
+        let ll = LogLine::test("/").with_addr("1.2.3.9").with_referer("https://dystroy.org").with_user_agent(FIREFOX);
+        assert_eq!(bc.classify(&ll), BotKind::Other("HEAD storm"));
+    }
+}
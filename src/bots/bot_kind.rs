@@ -0,0 +1,45 @@
+use {
+    std::fmt,
+};
+
+/// The result of the classification of a hit according
+/// to its user agent and to the behavior of its remote address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BotKind {
+    #[default]
+    Human,
+    /// a well behaved crawler, eg from a search engine
+    Good(&'static str),
+    /// any other bot, crawler, tool or script
+    Other(&'static str),
+}
+
+impl BotKind {
+    pub fn is_bot(self) -> bool {
+        !matches!(self, Self::Human)
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Human => "human",
+            Self::Good(name) => name,
+            Self::Other(name) => name,
+        }
+    }
+    pub fn category(self) -> &'static str {
+        match self {
+            Self::Human => "human",
+            Self::Good(_) => "known bot",
+            Self::Other(_) => "bot",
+        }
+    }
+}
+
+impl fmt::Display for BotKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Human => write!(f, "human"),
+            Self::Good(name) => write!(f, "{} (known)", name),
+            Self::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod bot_classifier;
+mod bot_kind;
+
+pub use {
+    bot_classifier::*,
+    bot_kind::*,
+};
@@ -0,0 +1,129 @@
+use {
+    crate::*,
+    minimad::OwningTemplateExpander,
+    std::collections::HashSet,
+    termimad::*,
+};
+
+static MD: &str = r#"
+|:-:|:-:|:-:|:-:|:-
+|**${bucket}**|**hits**|**visitors**|**bytes**|**${scale}**
+|:-|-:|-:|-:|:-
+${bars
+|${label}|${hits}|${visitors}|${bytes}|*${bar}*
+}
+|-:
+"#;
+
+static MD_RATES: &str = r#"
+|:-:|:-:|:-:|:-:|:-:|:-:|:-:|:-
+|**${bucket}**|**hits**|**visitors**|**bytes**|**p50**|**p95**|**p99**|**${scale}**
+|:-|-:|-:|-:|-:|-:|-:|:-
+${bars
+|${label}|${hits}|${visitors}|${bytes}|${p50}|${p95}|${p99}|*${bar}*
+}
+|-:
+"#;
+
+struct BucketBar {
+    index: i64,
+    hits: u64,
+    bytes_sent: u64,
+    visitors: u64,
+}
+
+/// An histogram of hits in time buckets of a chosen (or
+/// automatically chosen) size, from minutes to months
+pub struct BucketHistogram {
+    bucket: TimeBucket,
+    /// whether the bars don't all cover the same day
+    several_days: bool,
+    bars: Vec<BucketBar>,
+    /// percentiles of the hits per second, when asked, indexed like the bars
+    rates: Option<Vec<RatePercentiles>>,
+}
+
+impl BucketHistogram {
+
+    pub fn from(base: &LogBase, bucket: TimeBucket) -> Option<Self> {
+        let first = base.lines.iter().map(|line| line.date_time).min()?;
+        let last = base.lines.iter().map(|line| line.date_time).max()?;
+        let bucket = bucket.resolve(first, last);
+        let first_idx = bucket.index(first);
+        let last_idx = bucket.index(last);
+        // buckets without hit get a bar too
+        let mut bars: Vec<BucketBar> = (first_idx..=last_idx)
+            .map(|index| BucketBar {
+                index,
+                hits: 0,
+                bytes_sent: 0,
+                visitors: 0,
+            })
+            .collect();
+        let mut bar_visitors = HashSet::new();
+        for line in &base.lines {
+            let idx = (bucket.index(line.date_time) - first_idx) as usize;
+            bars[idx].hits += 1;
+            bars[idx].bytes_sent += line.bytes_sent;
+            if base.visitors && bar_visitors.insert((idx, line.visitor)) {
+                bars[idx].visitors += 1;
+            }
+        }
+        let several_days = first.date != last.date;
+        Some(Self { bucket, several_days, bars, rates: None })
+    }
+    /// Add the percentiles of the hits per second of every bucket
+    pub fn with_rates(mut self, base: &LogBase) -> Self {
+        if let (Some(first), Some(last)) = (self.bars.first(), self.bars.last()) {
+            self.rates = Some(bucket_rate_percentiles(base, self.bucket, first.index, last.index));
+        }
+        self
+    }
+
+    pub fn print(
+        &self,
+        printer: &md::Printer,
+    ) {
+        let mut expander = OwningTemplateExpander::new();
+        let max_bar = self.bars
+            .iter()
+            .map(|b| printer.key.pick(b.hits, b.bytes_sent, b.visitors))
+            .max().unwrap_or(0);
+        expander
+            .set("bucket", self.bucket.name())
+            .set(
+                "scale",
+                format!("0               {:>4}", file_size::fit_4(max_bar)),
+            );
+        let max_bar = max_bar.max(1) as f32;
+        for (idx, bar) in self.bars.iter().enumerate() {
+            // a bar is hidden when all its days are excluded by date filters
+            let (start, end) = self.bucket.days(bar.index);
+            let shown = (start.days_since_epoch()..=end.days_since_epoch())
+                .any(|day| printer.shows_date(Date::from_days_since_epoch(day)));
+            if !shown {
+                continue;
+            }
+            let value = printer.key.pick(bar.hits, bar.bytes_sent, bar.visitors);
+            let part = (value as f32) / max_bar;
+            let sub = expander.sub("bars");
+            sub
+                .set("label", self.bucket.label(bar.index, self.several_days))
+                .set_md("hits", printer.md_hits(bar.hits as usize))
+                .set_md("visitors", printer.md_visitors(bar.visitors as usize))
+                .set_md("bytes", printer.md_bytes(bar.bytes_sent))
+                .set("bar", ProgressBar::new(part, 20));
+            if let Some(rates) = self.rates.as_ref().and_then(|rates| rates.get(idx)) {
+                sub
+                    .set("p50", rates.p50)
+                    .set("p95", rates.p95)
+                    .set("p99", rates.p99);
+            }
+        }
+        if self.rates.is_some() {
+            printer.print(expander, MD_RATES);
+        } else {
+            printer.print(expander, MD);
+        }
+    }
+}
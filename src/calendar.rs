@@ -0,0 +1,144 @@
+use {
+    crate::*,
+    termimad::minimad::OwningTemplateExpander,
+    std::collections::BTreeMap,
+};
+
+static MD_YEAR: &str = r#"
+|:-:|:-
+|**${year}**|**${months}**
+|:-|:-
+${weekdays
+|${weekday}|*${days}*
+}
+|-
+"#;
+
+static MD_LEGEND: &str = r#"
+*·* no hit, *░▒▓█* up to ${max}. ${missing}
+"#;
+
+/// A GitHub-like calendar of the days of the observed period,
+/// with one line per day of the week and one column per week.
+///
+/// Contrary to the dates histogram, it explicitly shows the days
+/// without any hit.
+pub struct Calendar {
+    first: Date,
+    last: Date,
+    days: BTreeMap<Date, DateBar>,
+}
+
+impl Calendar {
+
+    pub fn from(base: &LogBase) -> Option<Self> {
+        let first = *base.dates.first()?;
+        let last = *base.dates.last()?;
+        let days = base.filtered_histogram.bars
+            .iter()
+            .map(|bar| (bar.date, bar.clone()))
+            .collect();
+        Some(Self { first, last, days })
+    }
+
+    fn value(&self, date: Date, key: Key) -> u64 {
+        self.days
+            .get(&date)
+            .map_or(0, |bar| key.pick(bar.hits, bar.bytes_sent, bar.visitors))
+    }
+
+    pub fn print(
+        &self,
+        printer: &md::Printer,
+    ) {
+        let key = printer.key;
+        let first_day = self.first.days_since_epoch();
+        let last_day = self.last.days_since_epoch();
+        let shown_days: Vec<Date> = (first_day..=last_day)
+            .map(Date::from_days_since_epoch)
+            .filter(|&date| printer.shows_date(date))
+            .collect();
+        let (first, last) = match (shown_days.first(), shown_days.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => {
+                return;
+            }
+        };
+        // the calendar is restricted to the days not excluded by date filters
+        let first_day = first.days_since_epoch();
+        let last_day = last.days_since_epoch();
+        let max = shown_days
+            .iter()
+            .map(|&date| self.value(date, key))
+            .max()
+            .unwrap_or(0);
+        let missing = shown_days
+            .iter()
+            .filter(|date| !self.days.contains_key(date))
+            .count();
+        printer.skin.print_text(&format!("## {} per day", key.name()));
+        for year in first.year..=last.year {
+            // the calendar of the year starts on the monday of its first week
+            let start = first_day.max(Date { year, month: 1, day: 1 }.days_since_epoch());
+            let end = last_day.min(Date { year, month: 12, day: 31 }.days_since_epoch());
+            let monday = start - Date::from_days_since_epoch(start).weekday() as i64;
+            let weeks = ((end - monday) / 7 + 1) as usize;
+            // the name of a month is written over the week of its first day,
+            // or over the first free weeks of the month when it would touch
+            // the previous name. The first week is always labelled.
+            let mut months = vec!['\u{a0}'; weeks + 6];
+            for day in start..=end {
+                let date = Date::from_days_since_epoch(day);
+                if date.day != 1 && day != start {
+                    continue;
+                }
+                let week = ((day - monday) / 7) as usize;
+                let free = |w: usize| (w.saturating_sub(1)..w + 3).all(|i| months[i] == '\u{a0}');
+                let label_week = if day == start {
+                    Some(week)
+                } else {
+                    (week..=week + 3).find(|&w| free(w))
+                };
+                if let Some(label_week) = label_week {
+                    for (i, c) in MONTHS_3_LETTERS[date.month as usize - 1].chars().enumerate() {
+                        months[label_week + i] = c;
+                    }
+                }
+            }
+            while months.len() > weeks && months.last() == Some(&'\u{a0}') {
+                months.pop();
+            }
+            let mut expander = OwningTemplateExpander::new();
+            expander
+                .set("year", year)
+                .set("months", months.into_iter().collect::<String>());
+            for (weekday, weekday_name) in WEEKDAYS_3_LETTERS.iter().enumerate() {
+                let days: String = (0..weeks)
+                    .map(|week| {
+                        let day = monday + (7 * week + weekday) as i64;
+                        let date = Date::from_days_since_epoch(day);
+                        if day < start || day > end || !printer.shows_date(date) {
+                            '\u{a0}'
+                        } else {
+                            shade(self.value(date, key), max)
+                        }
+                    })
+                    .collect();
+                expander.sub("weekdays")
+                    .set("weekday", weekday_name)
+                    .set("days", days);
+            }
+            printer.print(expander, MD_YEAR);
+        }
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set_default("")
+            .set_md("max", printer.md_key_sum(max));
+        match missing {
+            0 => {}
+            1 => { expander.set("missing", "1 day is missing from the logs."); }
+            n => { expander.set("missing", format!("{} days are missing from the logs.", n)); }
+        }
+        printer.print(expander, MD_LEGEND);
+    }
+}
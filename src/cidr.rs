@@ -0,0 +1,172 @@
+use {
+    std::{
+        fmt,
+        net::{AddrParseError, IpAddr},
+        num::ParseIntError,
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum ParseCidrError {
+    #[error("invalid IP address")]
+    InvalidAddr(#[from] AddrParseError),
+    #[error("invalid prefix length")]
+    InvalidPrefixLength(#[from] ParseIntError),
+    #[error("prefix length too big: {0}")]
+    PrefixLengthTooBig(u8),
+}
+
+/// A block of IP addresses, either IPv4 or IPv6, eg `10.0.0.0/8`.
+///
+/// A single address is a block with the maximal prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cidr {
+    pub addr: IpAddr,
+    pub prefix_len: u8,
+}
+
+fn mask(bits: u8, prefix_len: u8) -> u128 {
+    if prefix_len == 0 {
+        0
+    } else {
+        (u128::MAX << (128 - prefix_len)) >> (128 - bits)
+    }
+}
+
+fn to_bits(addr: IpAddr) -> (u128, u8) {
+    match addr {
+        IpAddr::V4(addr) => (u32::from(addr) as u128, 32),
+        IpAddr::V6(addr) => (u128::from(addr), 128),
+    }
+}
+
+impl Cidr {
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ParseCidrError> {
+        let (bits, len) = to_bits(addr);
+        if prefix_len > len {
+            return Err(ParseCidrError::PrefixLengthTooBig(prefix_len));
+        }
+        // we normalize the address so that equal blocks are equal
+        let bits = bits & mask(len, prefix_len);
+        let addr = match addr {
+            IpAddr::V4(_) => IpAddr::from((bits as u32).to_be_bytes()),
+            IpAddr::V6(_) => IpAddr::from(bits.to_be_bytes()),
+        };
+        Ok(Self { addr, prefix_len })
+    }
+    pub fn single(addr: IpAddr) -> Self {
+        let (_, len) = to_bits(addr);
+        Self { addr, prefix_len: len }
+    }
+    pub fn contains(&self, candidate: IpAddr) -> bool {
+        let (bits, len) = to_bits(self.addr);
+        let (cbits, clen) = to_bits(candidate);
+        len == clen && (cbits & mask(len, self.prefix_len)) == bits
+    }
+    /// parse the remote address of a log line and tell whether it's
+    /// in the block
+    pub fn contains_str(&self, candidate: &str) -> bool {
+        candidate.parse().map_or(false, |addr| self.contains(addr))
+    }
+}
+
+/// Merge blocks into the shortest list of blocks covering
+/// exactly the same addresses
+pub fn aggregate_cidrs(mut cidrs: Vec<Cidr>) -> Vec<Cidr> {
+    cidrs.sort_by_key(|cidr| {
+        let (bits, len) = to_bits(cidr.addr);
+        (len, bits, cidr.prefix_len)
+    });
+    let mut merged: Vec<Cidr> = Vec::with_capacity(cidrs.len());
+    for cidr in cidrs {
+        if let Some(last) = merged.last() {
+            if last.prefix_len <= cidr.prefix_len && last.contains(cidr.addr) {
+                continue;
+            }
+        }
+        merged.push(cidr);
+        // while the two last blocks are the halves of a bigger one, we merge them
+        while merged.len() > 1 {
+            let b = merged[merged.len() - 1];
+            let a = merged[merged.len() - 2];
+            let (a_bits, a_len) = to_bits(a.addr);
+            let (b_bits, b_len) = to_bits(b.addr);
+            if a_len != b_len || a.prefix_len != b.prefix_len || a.prefix_len == 0 {
+                break;
+            }
+            let half = 1u128 << (a_len - a.prefix_len);
+            if a_bits & half != 0 || a_bits | half != b_bits {
+                break;
+            }
+            merged.truncate(merged.len() - 2);
+            merged.push(Cidr { addr: a.addr, prefix_len: a.prefix_len - 1 });
+        }
+    }
+    merged
+}
+
+impl FromStr for Cidr {
+    type Err = ParseCidrError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => Self::new(addr.parse()?, prefix_len.parse()?),
+            None => Ok(Self::single(s.parse()?)),
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, len) = to_bits(self.addr);
+        if self.prefix_len == len {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix_len)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod cidr_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_cidr_v4() {
+        let cidr = Cidr::from_str("10.1.0.0/16").unwrap();
+        assert_eq!(cidr.contains_str("10.1.2.3"), true);
+        assert_eq!(cidr.contains_str("10.2.2.3"), false);
+        assert_eq!(cidr.contains_str("::1"), false);
+        assert_eq!(cidr.contains_str("garbage"), false);
+        let cidr = Cidr::from_str("192.168.1.12").unwrap();
+        assert_eq!(cidr.contains_str("192.168.1.12"), true);
+        assert_eq!(cidr.contains_str("192.168.1.13"), false);
+        assert_eq!(Cidr::from_str("10.1.2.3/8").unwrap().to_string(), "10.0.0.0/8");
+        assert!(Cidr::from_str("10.1.2.3/33").is_err());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let cidrs = ["10.0.0.1", "10.0.0.0", "10.0.0.3", "10.0.0.2", "10.0.0.5", "10.0.0.4/31", "::1"]
+            .iter()
+            .map(|s| Cidr::from_str(s).unwrap())
+            .collect();
+        let aggregated: Vec<String> = aggregate_cidrs(cidrs)
+            .iter()
+            .map(|cidr| cidr.to_string())
+            .collect();
+        assert_eq!(aggregated, vec!["10.0.0.0/30", "10.0.0.4/31", "::1"]);
+    }
+
+    #[test]
+    fn test_cidr_v6() {
+        let cidr = Cidr::from_str("2001:db8::/32").unwrap();
+        assert_eq!(cidr.contains_str("2001:db8:1::5"), true);
+        assert_eq!(cidr.contains_str("2001:db9::5"), false);
+        assert_eq!(cidr.contains_str("10.0.0.1"), false);
+    }
+}
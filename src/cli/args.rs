@@ -1,103 +1,322 @@
 use {
     crate::{
+        BlocklistFormat,
+        BotFilter,
+        CompareSort,
+        Config,
+        CrossFields,
+        CustomGrouping,
+        DrillFields,
+        Field,
         Key,
         Fields,
+        LineField,
         Output,
+        TimeBucket,
+        TrendBucket,
     },
-    clap::{Parser, ValueEnum},
+    clap::{Parser, Subcommand, ValueEnum},
     std::path::PathBuf,
     termimad::crossterm::tty::IsTty,
 };
 
 /// Program launch argument
+///
+/// Options are global so that they apply to the diff subcommand too,
+/// even when given after it
 #[derive(Debug, Default, Parser)]
 #[command(author, about, name = "rhit", disable_version_flag = true, version, disable_help_flag = true)]
+#[command(subcommand_precedence_over_arg = true)]
 pub struct Args {
 
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Print help information
-    #[arg(long)]
+    #[arg(global = true, long)]
     pub help: bool,
 
     /// Print the version
-    #[arg(long)]
+    #[arg(global = true, long)]
     pub version: bool,
 
     /// Whether to have styles and colors
-    #[arg(long, default_value="auto", value_name = "color")]
+    #[arg(global = true, long, default_value="auto", value_name = "color")]
     pub color: TriBool,
 
-    /// Key used in sorting and histogram, either `hits` or `bytes`
-    #[arg(short, long, default_value="hits")]
+    /// Key used in sorting and histogram, either `hits`, `bytes` or `visitors`
+    #[arg(global = true, short, long, default_value="hits")]
     pub key: Key,
 
     /// Detail level, from `0` to `6`, impacts the lengths of tables
-    #[arg(short, long, default_value = "1")]
+    #[arg(global = true, short, long, default_value = "1")]
     pub length: usize,
 
     /// Comma separated list of hit fields to display.
-    /// Use `-f a` to get all fields.
+    /// Use `-f a` to get all standard fields.
     /// Use `-f +i` to add ip.
-    /// Available fields: `date,time,method,status,ip,ref,path`.
-    #[arg(short, long, default_value = "date,status,ref,path")]
+    /// Standard fields: `date,time,method,status,ip,ref,path`.
+    /// Other fields: `bots,tree,domains,sources,sessions,entries,exits,broken,security,anomalies,tags,heatmap,calendar,peaks,distributions`.
+    #[arg(global = true, short, long, default_value = "date,status,ref,path")]
     pub fields: Fields,
 
+    /// An additional table, whose key is made of the capture groups of a
+    /// regular expression applied to a field
+    /// (eg `--group-by 'lang=path:^/(fr|en)/'`)
+    #[arg(global = true, long, value_name = "name=field:regex")]
+    pub group_by: Vec<CustomGrouping>,
+
+    /// Two comma separated fields to cross, in a table whose rows are the
+    /// values of the first field and columns the values of the second one
+    /// (eg `--cross paths,status` or `--cross ip,method`)
+    #[arg(global = true, long, value_name = "rows,cols")]
+    pub cross: Vec<CrossFields>,
+
+    /// A field whose most frequent values are detailed with the most
+    /// frequent values of other fields (eg `--drill paths:referers,ip`)
+    #[arg(global = true, long, value_name = "field:fields")]
+    pub drill: Vec<DrillFields>,
+
     /// Add tables with more popular and less popular entries (ip, referers and paths)
-    #[arg(short, long)]
+    #[arg(global = true, short, long)]
     pub changes: bool,
 
+    /// Number of the most recent days (or hours) whose hits are compared
+    /// to the previous ones in trends (default: 2)
+    #[arg(global = true, long, value_name = "n")]
+    pub trend_tail: Option<usize>,
+
+    /// Number of days (or hours) before the tail, to which it's compared
+    /// in trends (default: 18)
+    #[arg(global = true, long, value_name = "n")]
+    pub trend_ref: Option<usize>,
+
+    /// Time slot of histograms and trends in tables, `day` or `hour`
+    #[arg(global = true, long, value_name = "bucket")]
+    pub trend_bucket: Option<TrendBucket>,
+
+    /// Time slot of the bars of the dates table: `auto`, `1m`, `5m`, `15m`,
+    /// `1h`, `1d`, `1w` or `1M`. With `auto`, it's chosen according to the
+    /// time span of the hits
+    #[arg(global = true, long, value_name = "bucket")]
+    pub bucket: Option<TimeBucket>,
+
+    /// Add the median, 95th and 99th percentiles of the hits per second
+    /// of every day (or bucket, with `--bucket`) to the dates table
+    #[arg(global = true, long)]
+    pub rps: bool,
+
+    /// Compare two periods, given as date ranges, eg
+    /// `--compare 2024/03/11-2024/03/17 2024/03/04-2024/03/10`.
+    /// When the second one is missing, the first one is compared to the
+    /// period of the same length before it. When no period is given, the
+    /// last week is compared to the previous one
+    #[arg(global = true, long, num_args = 0..=2, value_name = "range")]
+    pub compare: Option<Vec<String>>,
+
+    /// Order of rows in comparisons: `total`, `gain`, `loss` or `change`
+    #[arg(global = true, long, default_value = "total", value_name = "order")]
+    pub compare_sort: CompareSort,
+
     /// Filter the dates on a precise day or in an inclusive range
     /// (eg: `-d 12/24` or `-d '2021/12/24-2022/01/21'`)
-    #[arg(short, long)]
-    pub date: Option<String>,
+    #[arg(global = true, short, long)]
+    pub date: Vec<String>,
 
     /// Ip address to filter by. May be negated with a `!`
-    #[arg(short, long)]
-    pub ip: Option<String>,
+    #[arg(global = true, short, long)]
+    pub ip: Vec<String>,
 
-    /// HTTP method to filter by. Make it negative with a `!`.
-    /// (eg: `-m PUT` or `-m !DELETE` or `-m none` or `-m other`)
-    #[arg(short, long)]
-    pub method: Option<String>,
+    /// Comma separated list of HTTP methods to filter by. Make them negative with a `!`.
+    /// (eg: `-m PUT` or `-m GET,POST` or `-m '!GET,!HEAD'` or `-m none` or `-m other`)
+    #[arg(global = true, short, long)]
+    pub method: Vec<String>,
 
     /// Pattern for path filtering
-    /// (eg: `-p broot` or `-p '^/\d+'` or `-p 'miaou | blog'`)
-    #[arg(short, long)]
-    pub path: Option<String>,
+    /// (eg: `-p broot` or `-p '^/\d+'` or `-p 'miaou | blog'`).
+    /// Patterns may be prefixed with `i:` (case insensitive),
+    /// `g:` (glob) or `=` (exact). To match a literal `i:`, `g:` or `=`
+    /// at the start of a pattern, escape it, eg `\=` or `[i]:`
+    #[arg(global = true, short, long)]
+    pub path: Vec<String>,
 
     /// Referrer filter
-    #[arg(short, long)]
-    pub referer: Option<String>,
+    #[arg(global = true, short, long)]
+    pub referer: Vec<String>,
+
+    /// Filter on the host of the referrer
+    /// (eg: `--referer-domain github.com` or `--referer-domain '!google'`)
+    #[arg(global = true, long, value_name = "pattern")]
+    pub referer_domain: Vec<String>,
+
+    /// One of your own domains, whose referrers are internal ones.
+    /// Added to the `own_domains` of the configuration
+    #[arg(global = true, long, value_name = "domain")]
+    pub own_domain: Vec<String>,
+
+    /// Group the referrer hosts by registrable domain
+    /// (eg `blog.example.co.uk` as `example.co.uk`)
+    #[arg(global = true, long)]
+    pub registrable_domains: bool,
 
     /// Comma separated list of statuses or status ranges to filter by
     /// (eg: `-s 514` or `-s 4xx,5xx`, or `-s 310-340,400-450` or `-s 5xx,!502`)
-    #[arg(short, long)]
-    pub status: Option<String>,
+    #[arg(global = true, short, long)]
+    pub status: Vec<String>,
+
+    /// Comma separated list of tags to filter by. Make them negative with a `!`.
+    /// (eg: `--tag api` or `--tag '!static'`)
+    #[arg(global = true, long)]
+    pub tag: Vec<String>,
 
     /// Filter the time of the day, in the logs' timezone
     /// (eg: `-t '>19:30'` to get evening hits)
-    #[arg(short, long)]
-    pub time: Option<String>,
+    #[arg(global = true, short, long)]
+    pub time: Vec<String>,
+
+    /// Whether to `include` the hits of bots and crawlers, keep `only` them,
+    /// or `exclude` them
+    #[arg(global = true, long, default_value="include", value_name = "bots")]
+    pub bots: BotFilter,
+
+    /// Exclude the hits of bots and crawlers (same as `--bots exclude`)
+    #[arg(global = true, long)]
+    pub no_bots: bool,
+
+    /// A file with additional bot signatures, one per line, in the form
+    /// `good Googlebot` or `bot curl`
+    #[arg(global = true, long, value_name = "file")]
+    pub bot_signatures: Option<PathBuf>,
+
+    /// Exclude the hits of vulnerability scanners: the probes matching
+    /// a scan rule, and the following hits of the same addresses
+    #[arg(global = true, long)]
+    pub exclude_scanners: bool,
+
+    /// A file with additional scan rules, one per line, in the form
+    /// `<name> <regex on path>`
+    #[arg(global = true, long, value_name = "file")]
+    pub scan_rules: Option<PathBuf>,
+
+    /// A file with additional tag rules, one per line, in the form
+    /// `<tag>: <field> <pattern> & <field> <pattern>`
+    #[arg(global = true, long, value_name = "file")]
+    pub tag_rules: Option<PathBuf>,
+
+    /// Path to the configuration file. If not provided, rhit looks
+    /// for `rhit/rhit.toml` in the user's configuration directory
+    #[arg(global = true, long = "config", value_name = "file")]
+    pub config_path: Option<PathBuf>,
+
+    /// Don't apply the exclusion profiles of the configuration
+    #[arg(global = true, long)]
+    pub no_exclusions: bool,
+
+    /// Replace paths with route templates, eg `/user/123` with `/user/{id}`,
+    /// in tables, trends and filters
+    #[arg(global = true, long)]
+    pub templates: bool,
+
+    /// Count the distinct visitors, shown in the summary and in a
+    /// column of the tables (implied by `--key visitors`)
+    #[arg(global = true, long)]
+    pub visitors: bool,
+
+    /// Identify visitors by remote address and user agent, instead
+    /// of remote address only
+    #[arg(global = true, long)]
+    pub visitor_ua: bool,
+
+    /// Inactivity timeout, in minutes, after which a new hit
+    /// of a visitor starts a new session
+    #[arg(global = true, long, default_value = "30", value_name = "minutes")]
+    pub session_timeout: u32,
+
+    /// Minimal robust z-score of the hits of a day (or hour) for it
+    /// to be reported as an anomaly
+    #[arg(global = true, long, default_value = "3.5", value_name = "score")]
+    pub anomaly_threshold: f64,
 
     /// Show all paths, including resources
-    #[arg(short, long)]
+    #[arg(global = true, short, long)]
     pub all: bool,
 
+    /// Max depth of the directories in the path tree
+    #[arg(global = true, long, default_value = "3")]
+    pub tree_depth: usize,
+
     /// Try to open all files, whatever their names
-    #[arg(long)]
+    #[arg(global = true, long)]
     pub no_name_check: bool,
 
     /// Output: by default pretty summary tables but you can also
-    /// output log lines as `csv`, `json`, or `raw` (as they appear in the log files)
-    #[arg(short, long, default_value="tables")]
+    /// output log lines as `csv`, `json`, or `raw` (as they appear in the log files),
+    /// the remote addresses of the accepted lines as a `blocklist`,
+    /// the detected `anomalies` as JSON, or the `distributions` of hits
+    /// per IP, bytes per response and hits per path as JSON
+    #[arg(global = true, short, long, default_value="tables")]
     pub output: Output,
 
+    /// Format of the blocklist output
+    #[arg(global = true, long, default_value="plain", value_name = "format")]
+    pub blocklist_format: BlocklistFormat,
+
+    /// Write the blocklist in this file instead of the standard output
+    #[arg(global = true, long, value_name = "file")]
+    pub blocklist_file: Option<PathBuf>,
+
+    /// Minimal number of hits of an address to be in the blocklist
+    #[arg(global = true, long, value_name = "hits")]
+    pub min_hits: Option<u64>,
+
+    /// Minimal part, from 0 to 1, of the hits of an address having
+    /// an error status (4xx or 5xx) for it to be in the blocklist
+    #[arg(global = true, long, value_name = "ratio")]
+    pub min_error_ratio: Option<f32>,
+
+    /// Minimal average number of hits per minute of an address
+    /// for it to be in the blocklist
+    #[arg(global = true, long, value_name = "rate")]
+    pub min_rate: Option<f32>,
+
+    /// Name of the ipset or of the fail2ban jail, in blocklists
+    #[arg(global = true, long, default_value = "rhit", value_name = "name")]
+    pub ipset_name: String,
+
+    /// Print how the filters were understood
+    #[arg(global = true, long)]
+    pub explain_filters: bool,
+
+    /// Print how the filters apply to the log line at the given
+    /// number (starting at 1, in reading order, invalid lines included)
+    #[arg(global = true, long, value_name = "n")]
+    pub explain_line: Option<usize>,
+
+    /// Print how the filters apply to the given log line
+    #[arg(global = true, long, value_name = "line")]
+    pub explain: Option<String>,
+
     /// Don't print anything during load: no progress bar or file list
-    #[arg(long)]
+    #[arg(global = true, long)]
     pub silent_load: bool,
 
     /// The log file or folder to analyze. It not provided, logs will be opened
     /// at their standard location
     pub files: Vec<PathBuf>,
+
+    /// The content of the configuration file, not a launch argument
+    #[arg(skip)]
+    pub config: Config,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Compare two log sets, with the same filters
+    Diff {
+        /// The log files or folders to compare, the first one
+        /// being possibly given before `diff`
+        logs: Vec<PathBuf>,
+    },
 }
 
 #[derive(ValueEnum)]
@@ -125,4 +344,46 @@ impl Args {
     pub fn color(&self) -> bool {
         self.color.unwrap_or_else(|| std::io::stdout().is_tty())
     }
+    /// Tell whether the distinct visitors must be counted, which
+    /// is costly so only done when they're displayed or sorted on
+    pub fn counts_visitors(&self) -> bool {
+        self.visitors || self.key == Key::Visitors
+    }
+    /// Tell whether a log line is explained, with all its characteristics
+    pub fn explains_line(&self) -> bool {
+        self.explain_line.is_some() || self.explain.is_some()
+    }
+    /// Tell whether a field of the lines is used in a cross table,
+    /// a drill down or a custom grouping
+    pub fn uses_line_field(&self, field: LineField) -> bool {
+        self.cross.iter().any(|cross| cross.rows == field || cross.cols == field)
+            || self.drill.iter().any(|drill| {
+                drill.parent == field || drill.children.contains(&field)
+            })
+            || self.group_by.iter().any(|grouping| grouping.field == field)
+    }
+    /// Tell whether the hits must be classified as human or bot ones
+    pub fn classifies_bots(&self) -> bool {
+        self.no_bots
+            || self.bots != BotFilter::Include
+            || self.fields.contains(Field::Bots)
+            || self.uses_line_field(LineField::Bot)
+            || self.explains_line()
+    }
+    /// Tell whether the hits of vulnerability scanners must be detected
+    pub fn detects_scanners(&self) -> bool {
+        self.exclude_scanners
+            || self.fields.contains(Field::Security)
+            || self.explains_line()
+    }
+    /// Tell whether the lines must be tagged: when tags are filtered
+    /// or displayed, or when the user defined tag rules
+    pub fn tags_lines(&self) -> bool {
+        !self.tag.is_empty()
+            || self.tag_rules.is_some()
+            || self.fields.contains(Field::Tags)
+            || self.uses_line_field(LineField::Tags)
+            || matches!(self.output, Output::Csv | Output::Json)
+            || self.explains_line()
+    }
 }
@@ -13,6 +13,8 @@ use {
 static INTRO_TEMPLATE: &str = "
 **Rhit** analyzes your nginx logs.
 
+Use `rhit diff <A> <B>` to compare two log sets.
+
 Complete documentation at *https://dystroy.org/rhit*
 
 ";
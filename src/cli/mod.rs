@@ -3,10 +3,10 @@ mod help;
 
 use {
     crate::*,
-    args::Args,
+    args::{Args, Command},
     clap::Parser,
     cli_log::*,
-    std::path::PathBuf,
+    std::path::{Path, PathBuf},
 };
 
 const DEFAULT_NGINX_LOCATION: &str = "/var/log/nginx";
@@ -16,17 +16,39 @@ If necessary, provide the path to the log file(s) as argument.
 More information with 'rhit --help'.";
 
 fn print_analysis(paths: &[PathBuf], args: &args::Args) -> Result<(), RhitError> {
-    let mut log_base = time!("LogBase::new", LogBase::new(paths, args))?;
-    let printer = md::Printer::new(args, &log_base);
-    let base = &mut log_base;
-    let trend_computer = time!("Trend computer initialization", TrendComputer::new(base, args))?;
-    md::summary::print_summary(base, &printer);
+    let log_base = time!("LogBase::new", LogBase::new(paths, args))?;
+    let comparison = match &args.compare {
+        Some(periods) => Some(Comparison::new(periods, args.compare_sort, &log_base)?),
+        None => None,
+    };
+    // trends aren't shown when comparing periods
+    let trend_computer = if comparison.is_some() {
+        None
+    } else {
+        time!("Trend computer initialization", TrendComputer::new(&log_base, args))?
+    };
+    let printer = md::Printer::new(args, &log_base, comparison);
+    md::summary::print_summary(&log_base, &printer);
     time!("Analysis & Printing", md::print_analysis(&log_base, &printer, trend_computer.as_ref()));
     Ok(())
 }
 
+/// Compare two log sets, with the same filters
+fn print_diff(a: &Path, b: &Path, args: &args::Args) -> Result<(), RhitError> {
+    let base_a = time!("LogBase::new A", LogBase::new(&[a.to_path_buf()], args))?;
+    let base_b = time!("LogBase::new B", LogBase::new(&[b.to_path_buf()], args))?;
+    let printer = md::Printer::new(args, &base_a, None);
+    let diff = time!(LogDiff::new(&base_a, &base_b, args.all));
+    let names = [
+        a.to_string_lossy(),
+        b.to_string_lossy(),
+    ];
+    md::diff::print_diff(&diff, [&names[0], &names[1]], &printer);
+    Ok(())
+}
+
 pub fn run() -> Result<(), RhitError> {
-    let args = Args::parse();
+    let mut args = Args::parse();
     debug!("args: {:#?}", &args);
     if args.version {
         println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
@@ -36,15 +58,32 @@ pub fn run() -> Result<(), RhitError> {
         help::print();
         return Ok(());
     }
+    args.config = Config::load(args.config_path.as_deref())?;
+    if let Some(Command::Diff { logs }) = &args.command {
+        // both `rhit diff A B` and `rhit A diff B` are accepted
+        let logs: Vec<&PathBuf> = args.files.iter().chain(logs).collect();
+        if logs.len() != 2 {
+            return Err(RhitError::DiffArity(logs.len()));
+        }
+        return print_diff(logs[0], logs[1], &args);
+    }
     let mut paths = args.files.clone();
     if paths.is_empty() {
         paths.push(PathBuf::from(DEFAULT_NGINX_LOCATION));
     }
-    let result = match args.output {
-        Output::Raw => print_raw_lines(&paths, &args),
-        Output::Tables => print_analysis(&paths, &args),
-        Output::Csv => print_csv_lines(&paths, &args),
-        Output::Json => print_json_lines(&paths, &args),
+    let explain = args.explain_filters || args.explain_line.is_some() || args.explain.is_some();
+    let result = if explain {
+        print_explanations(&paths, &args)
+    } else {
+        match args.output {
+            Output::Raw => print_raw_lines(&paths, &args),
+            Output::Tables => print_analysis(&paths, &args),
+            Output::Csv => print_csv_lines(&paths, &args),
+            Output::Json => print_json_lines(&paths, &args),
+            Output::Blocklist => print_blocklist(&paths, &args),
+            Output::Anomalies => print_anomalies_json(&paths, &args),
+            Output::Distributions => print_distributions_json(&paths, &args),
+        }
     };
     if let Err(RhitError::PathNotFound(ref path)) = result {
         if path == &PathBuf::from(DEFAULT_NGINX_LOCATION) {
@@ -0,0 +1,160 @@
+use {
+    crate::*,
+    clap::ValueEnum,
+    std::fmt,
+};
+
+/// Number of days of the compared periods when none is given
+const DEFAULT_PERIOD_DAYS: i64 = 7;
+
+/// An inclusive range of days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub start: Date,
+    pub end: Date,
+}
+
+impl Period {
+    /// Parse a period, with the syntax of date filters
+    /// (eg `2024/03/11-2024/03/17`, `03/12`, `2024/03` or `>03/20`)
+    pub fn parse(s: &str, base: &LogBase) -> Result<Self, RhitError> {
+        let (default_year, default_month) = unique_year_month(base.start_time(), base.end_time());
+        let filter = DateTimeFilter::new(s, default_year, default_month)?;
+        let (start, mut end) = match filter {
+            DateTimeFilter::Range(a, b) => (a.date, b.date),
+            DateTimeFilter::PreciseDate(date) => (date, date),
+            DateTimeFilter::PreciseDateTime(datetime) => (datetime.date, datetime.date),
+            DateTimeFilter::AfterDate(date) => (date.add_days(1), base.end_time()),
+            DateTimeFilter::AfterDateTime(datetime) => (datetime.date, base.end_time()),
+            DateTimeFilter::BeforeDate(date) => (base.start_time(), date.add_days(-1)),
+            DateTimeFilter::BeforeDateTime(datetime) => (base.start_time(), datetime.date),
+            _ => {
+                return Err(RhitError::InvalidPeriod(s.to_owned()));
+            }
+        };
+        // month ranges end on the 31st, whatever the month
+        while end.day > 28 && Date::from_days_since_epoch(end.days_since_epoch()) != end {
+            end.day -= 1;
+        }
+        if start > end {
+            return Err(RhitError::InvalidPeriod(s.to_owned()));
+        }
+        Ok(Self { start, end })
+    }
+    /// The last days of the base
+    pub fn last_days(base: &LogBase, days: i64) -> Self {
+        let end = base.end_time();
+        Self { start: end.add_days(1 - days), end }
+    }
+    pub fn day_count(self) -> i64 {
+        self.end.days_since_epoch() - self.start.days_since_epoch() + 1
+    }
+    /// The number of days of the period between `first` and `last`,
+    /// eg the days covered by the logs
+    pub fn covered_days(self, first: Date, last: Date) -> i64 {
+        let start = self.start.max(first).days_since_epoch();
+        let end = self.end.min(last).days_since_epoch();
+        (end - start + 1).max(0)
+    }
+    /// The period of the same length, just before this one
+    pub fn previous(self) -> Self {
+        Self {
+            start: self.start.add_days(-self.day_count()),
+            end: self.start.add_days(-1),
+        }
+    }
+    pub fn contains(self, date: Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{} to {}", self.start, self.end)
+        }
+    }
+}
+
+/// How the rows of comparison tables are sorted
+#[derive(ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompareSort {
+    /// Most frequent values in both periods first
+    #[default]
+    Total,
+    /// Biggest increases first
+    Gain,
+    /// Biggest decreases first
+    Loss,
+    /// Biggest changes, up or down, first
+    Change,
+}
+
+/// Two periods whose hits are compared: the studied one, `a`,
+/// and the reference one, `b`
+#[derive(Debug, Clone, Copy)]
+pub struct Comparison {
+    pub a: Period,
+    pub b: Period,
+    pub sort: CompareSort,
+}
+
+impl Comparison {
+    /// Build the comparison from zero, one or two periods. When
+    /// the reference period isn't given, it's the one just before
+    /// the studied period. When no period is given, the last week
+    /// is compared to the previous one.
+    pub fn new(
+        periods: &[String],
+        sort: CompareSort,
+        base: &LogBase,
+    ) -> Result<Self, RhitError> {
+        let a = match periods.first() {
+            Some(s) => Period::parse(s, base)?,
+            None => Period::last_days(base, DEFAULT_PERIOD_DAYS),
+        };
+        let b = match periods.get(1) {
+            Some(s) => Period::parse(s, base)?,
+            None => a.previous(),
+        };
+        Ok(Self { a, b, sort })
+    }
+    /// Return the index of the period containing the date: 0
+    /// for `a`, 1 for `b`
+    pub fn side(&self, date: Date) -> Option<usize> {
+        if self.a.contains(date) {
+            Some(0)
+        } else if self.b.contains(date) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod comparison_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_previous_period() {
+        let period = Period {
+            start: Date::new(2024, 3, 1).unwrap(),
+            end: Date::new(2024, 3, 7).unwrap(),
+        };
+        assert_eq!(period.day_count(), 7);
+        let previous = period.previous();
+        assert_eq!(previous.start, Date::new(2024, 2, 23).unwrap());
+        assert_eq!(previous.end, Date::new(2024, 2, 29).unwrap());
+        // logs starting on 2024/02/27 cover only 3 days of the previous period
+        let first = Date::new(2024, 2, 27).unwrap();
+        let last = Date::new(2024, 3, 7).unwrap();
+        assert_eq!(previous.covered_days(first, last), 3);
+        assert_eq!(period.covered_days(first, last), 7);
+        assert_eq!(previous.covered_days(last, last), 0);
+    }
+}
@@ -0,0 +1,110 @@
+use {
+    crate::*,
+    serde::Deserialize,
+    std::{
+        collections::BTreeMap,
+        env,
+        fs,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("IO error reading {0:?}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("invalid configuration in {0:?}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+}
+
+/// A set of hits to remove from all analyses, usually
+/// the traffic of the site's owners (office IPs, uptime
+/// checkers, health-check endpoints, etc.)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExclusionProfile {
+    /// IP addresses or CIDR blocks, eg `10.0.0.0/8`
+    pub ips: Vec<String>,
+    /// patterns (regular expressions) of paths
+    pub paths: Vec<String>,
+    /// patterns (regular expressions) of user agents
+    pub user_agents: Vec<String>,
+}
+
+/// The content of the optional configuration file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// named exclusion profiles, applied unless `--no-exclusions`
+    pub exclusions: BTreeMap<String, ExclusionProfile>,
+    /// path normalization, applied with `--templates`
+    pub templates: PathTemplatesConfig,
+    /// domains of the site, whose referers are internal ones
+    pub own_domains: Vec<String>,
+    /// periods compared in trends
+    pub trends: TrendsConfig,
+}
+
+impl Config {
+    /// Return the default location of the configuration file,
+    /// which doesn't necessarily exist
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_dir.join("rhit").join("rhit.toml"))
+    }
+    /// Load the configuration from the given path or, if there's
+    /// none, from the default location when there's a file there
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => {
+                    return Ok(Self::default());
+                }
+            },
+        };
+        debug!("reading configuration in {:?}", &path);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| ConfigError::Io(path.clone(), e))?;
+        toml::from_str(&content)
+            .map_err(|e| ConfigError::Toml(path, e))
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_exclusions() {
+        let config: Config = toml::from_str(r#"
+            [exclusions.office]
+            ips = ["10.0.0.0/8", "192.168.1.12"]
+
+            [exclusions.monitoring]
+            paths = ["^/health$"]
+            user_agents = ["UptimeRobot"]
+        "#).unwrap();
+        assert_eq!(config.exclusions.len(), 2);
+        assert_eq!(config.exclusions["office"].ips.len(), 2);
+        assert!(config.exclusions["office"].paths.is_empty());
+        assert_eq!(config.exclusions["monitoring"].user_agents, vec!["UptimeRobot"]);
+    }
+
+    #[test]
+    fn parse_trends() {
+        let config: Config = toml::from_str(r#"
+            [trends]
+            tail = 7
+            bucket = "hour"
+        "#).unwrap();
+        assert_eq!(config.trends.tail, Some(7));
+        assert_eq!(config.trends.reference, None);
+        assert_eq!(config.trends.bucket, Some(TrendBucket::Hour));
+    }
+}
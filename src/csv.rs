@@ -6,14 +6,16 @@ use {
 };
 
 /// A printer writing lines as a CSV file
-struct CsvPrinter {}
+struct CsvPrinter {
+    tag_names: Vec<&'static str>,
+}
 
 impl LineConsumer for CsvPrinter {
     fn start_eating(
         &mut self,
         _first_date: Date,
     ) {
-        println!("date,time,remote address,method,path,status,bytes sent,referer");
+        println!("date,time,remote address,method,path,status,bytes sent,referer,tags");
     }
     fn eat_line(
         &mut self,
@@ -23,7 +25,7 @@ impl LineConsumer for CsvPrinter {
     ) {
         if filtered_out { return; }
         println!(
-            r#"{},{},{},{},"{}",{},{},"{}""#,
+            r#"{},{},{},{},"{}",{},{},"{}","{}""#,
             line.date(),
             line.time(),
             line.remote_addr,
@@ -32,6 +34,7 @@ impl LineConsumer for CsvPrinter {
             line.status,
             line.bytes_sent,
             line.referer,
+            line.tags.join(&self.tag_names, ","),
         );
     }
 }
@@ -40,8 +43,10 @@ pub fn print_csv_lines(
     path: &[PathBuf],
     args: &args::Args,
 ) -> Result<(), RhitError> {
-    let mut printer = CsvPrinter{};
-    let mut file_reader = FileReader::new(path, args, &mut printer)?;
+    let tagger = Tagger::new(args.tag_rules.as_deref())?;
+    let tag_names = tagger.names().to_vec();
+    let mut printer = CsvPrinter { tag_names };
+    let mut file_reader = FileReader::with_tagger(path, args, tagger, &mut printer)?;
     time!("reading files", file_reader.read_all_files())?;
     Ok(())
 }
@@ -0,0 +1,97 @@
+use {
+    crate::*,
+    lazy_regex::regex::{self, Regex},
+    std::{
+        borrow::Cow,
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// The value of the lines on which the regex doesn't match
+pub const NO_MATCH: &str = "(no match)";
+
+#[derive(Debug, Error)]
+pub enum ParseCustomGroupingError {
+    #[error("expected name=field:regex, got {0:?}")]
+    InvalidSyntax(String),
+    #[error("{0}")]
+    Field(#[from] ParseLineFieldError),
+    #[error("invalid regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+}
+
+/// An ad-hoc grouping, whose key is made of the capture groups
+/// of a regex applied to a field (eg `lang=path:^/(fr|en)/`)
+#[derive(Debug, Clone)]
+pub struct CustomGrouping {
+    pub name: &'static str,
+    pub field: LineField,
+    pub regex: Regex,
+}
+
+impl FromStr for CustomGrouping {
+    type Err = ParseCustomGroupingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rest) = s
+            .split_once('=')
+            .ok_or_else(|| ParseCustomGroupingError::InvalidSyntax(s.to_owned()))?;
+        let (field, regex) = rest
+            .split_once(':')
+            .ok_or_else(|| ParseCustomGroupingError::InvalidSyntax(s.to_owned()))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ParseCustomGroupingError::InvalidSyntax(s.to_owned()));
+        }
+        // the name is used in the static sections of tables
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        Ok(Self {
+            name,
+            field: field.parse()?,
+            regex: Regex::new(regex)?,
+        })
+    }
+}
+
+impl CustomGrouping {
+    /// Return the key of the line: the capture groups of the regex,
+    /// or the whole match when there's no group
+    pub fn key<'l>(&self, line: &'l LogLine, printer: &md::Printer) -> Cow<'l, str> {
+        let value = self.field.value(line, printer);
+        let key = self.regex.captures(&value).map(|captures| {
+            if captures.len() == 1 {
+                captures[0].to_owned()
+            } else {
+                captures
+                    .iter()
+                    .skip(1)
+                    .flatten()
+                    .map(|m| m.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            }
+        });
+        match key {
+            Some(key) => key.into(),
+            None => NO_MATCH.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod custom_grouping_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_grouping() {
+        let grouping: CustomGrouping = "lang=path:^/(fr|en)/".parse().unwrap();
+        assert_eq!(grouping.name, "lang");
+        assert_eq!(grouping.field, LineField::Path);
+        assert!(grouping.regex.is_match("/fr/blog"));
+        assert!("lang".parse::<CustomGrouping>().is_err());
+        assert!("lang=path".parse::<CustomGrouping>().is_err());
+        assert!("lang=colors:^/".parse::<CustomGrouping>().is_err());
+        assert!("lang=path:^/(".parse::<CustomGrouping>().is_err());
+    }
+}
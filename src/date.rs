@@ -7,6 +7,11 @@ pub static MONTHS_3_LETTERS: &[&str] = &[
     "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
 ];
 
+/// the days of the week, in the order of `Date::weekday`
+pub static WEEKDAYS_3_LETTERS: &[&str] = &[
+    "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"
+];
+
 /// a not precise date, only valid in the context
 /// of the local set of log files.
 /// It's implicitely in the timezone of the log files
@@ -21,6 +26,7 @@ pub struct Date {
 }
 
 impl Date {
+    #[allow(clippy::manual_range_contains)]
     pub fn new(year: u16, month: u8, day: u8) -> Result<Self, ParseDateTimeError> {
         if day < 1 || day > 31 {
             return Err(ParseDateTimeError::InvalidDay(day));
@@ -30,6 +36,42 @@ impl Date {
         }
         Ok(Self { year, month, day })
     }
+    /// Return the number of days since 1970/01/01 (negative before)
+    pub fn days_since_epoch(self) -> i64 {
+        // see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let y = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let m = self.month as i64;
+        let d = self.day as i64;
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+    /// Return the day of the week, from 0 (monday) to 6 (sunday)
+    pub fn weekday(self) -> u8 {
+        // 1970/01/01 was a thursday
+        (self.days_since_epoch() + 3).rem_euclid(7) as u8
+    }
+    /// Build the date from the number of days since 1970/01/01
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as u16;
+        Self { year, month, day }
+    }
+    /// Return the date the given number of days later (or
+    /// earlier when negative)
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days_since_epoch(self.days_since_epoch() + days)
+    }
     /// parse the date part of a nginx datetime.
     ///
     /// a datetime in nginx is either in
@@ -123,6 +165,18 @@ mod date_parsing_tests {
         );
     }
     #[test]
+    fn days_since_epoch() {
+        for (date, days) in [
+            (Date::new(1970, 1, 1).unwrap(), 0),
+            (Date::new(2000, 3, 1).unwrap(), 11017),
+            (Date::new(2024, 2, 29).unwrap(), 19782),
+        ] {
+            assert_eq!(date.days_since_epoch(), days);
+            assert_eq!(Date::from_days_since_epoch(days), date);
+        }
+        assert_eq!(Date::new(2024, 3, 18).unwrap().weekday(), 0);
+    }
+    #[test]
     fn parse_nginx_date_iso_8601() {
         assert_eq!(
             Date::from_nginx("1977-04-22T01:00:00-05:00").unwrap(),
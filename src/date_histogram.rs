@@ -1,15 +1,26 @@
 use {
     crate::*,
     minimad::OwningTemplateExpander,
+    std::collections::HashSet,
     termimad::*,
 };
 
 static MD: &str = r#"
-|:-:|:-:|:-:|:-
-|**date**|**hits**|**bytes**|**${scale}**
-|:-|-:|-:|:-
+|:-:|:-:|:-:|:-:|:-
+|**date**|**hits**|**visitors**|**bytes**|**${scale}**
+|:-|-:|-:|-:|:-
 ${bars
-|${date}|${hits}|${bytes}|*${bar}*
+|${date}|${hits}|${visitors}|${bytes}|*${bar}*
+}
+|-:
+"#;
+
+static MD_RATES: &str = r#"
+|:-:|:-:|:-:|:-:|:-:|:-:|:-:|:-
+|**date**|**hits**|**visitors**|**bytes**|**p50**|**p95**|**p99**|**${scale}**
+|:-|-:|-:|-:|-:|-:|-:|:-
+${bars
+|${date}|${hits}|${visitors}|${bytes}|${p50}|${p95}|${p99}|*${bar}*
 }
 |-:
 "#;
@@ -19,6 +30,7 @@ pub struct DateBar {
     pub date: Date,
     pub hits: u64,
     pub bytes_sent: u64,
+    pub visitors: u64,
 }
 
 impl DateBar {
@@ -27,6 +39,7 @@ impl DateBar {
             date,
             hits: 0,
             bytes_sent: 0,
+            visitors: 0,
         }
     }
 }
@@ -34,19 +47,35 @@ impl DateBar {
 #[derive(Clone, Default)]
 pub struct DateHistogram {
     pub bars: Vec<DateBar>,
+    /// percentiles of the hits per second, when asked, indexed like the bars
+    pub rates: Option<Vec<RatePercentiles>>,
 }
 
 impl DateHistogram {
 
     pub fn from(base: &LogBase) -> Self {
         let mut bars: Vec<DateBar> = base.dates.iter()
-            .map(|&date| DateBar { date, bytes_sent: 0, hits: 0 })
+            .map(|&date| DateBar::new(date))
             .collect();
+        let mut day_visitors = HashSet::new();
+        let mut day_idx = 0;
         for line in &base.lines {
+            if line.date_idx != day_idx {
+                day_visitors.clear();
+                day_idx = line.date_idx;
+            }
             bars[line.date_idx].hits += 1;
             bars[line.date_idx].bytes_sent += line.bytes_sent;
+            if base.visitors && day_visitors.insert(line.visitor) {
+                bars[line.date_idx].visitors += 1;
+            }
         }
-        Self { bars }
+        Self { bars, rates: None }
+    }
+    /// Add the percentiles of the hits per second of every day
+    pub fn with_rates(mut self, base: &LogBase) -> Self {
+        self.rates = Some(daily_rate_percentiles(base));
+        self
     }
 
     pub fn print(
@@ -56,25 +85,37 @@ impl DateHistogram {
         let mut expander = OwningTemplateExpander::new();
         let max_bar = self.bars
             .iter()
-            .map(|b| if printer.key==Key::Hits { b.hits } else { b.bytes_sent })
+            .map(|b| printer.key.pick(b.hits, b.bytes_sent, b.visitors))
             .max().unwrap();
         expander.set(
             "scale",
             format!("0               {:>4}", file_size::fit_4(max_bar)),
         );
         let max_bar = max_bar as f32;
-        for bar in &self.bars {
-            if printer.date_filter.map_or(true, |f| f.overlaps(bar.date)) {
-                let value = if printer.key == Key::Hits { bar.hits } else { bar.bytes_sent };
+        for (idx, bar) in self.bars.iter().enumerate() {
+            if printer.shows_date(bar.date) {
+                let value = printer.key.pick(bar.hits, bar.bytes_sent, bar.visitors);
                 let part = (value as f32) / max_bar;
-                expander.sub("bars")
+                let sub = expander.sub("bars");
+                sub
                     .set("date", bar.date)
                     .set_md("hits", printer.md_hits(bar.hits as usize))
+                    .set_md("visitors", printer.md_visitors(bar.visitors as usize))
                     .set_md("bytes", printer.md_bytes(bar.bytes_sent))
                     .set("bar", ProgressBar::new(part, 20));
+                if let Some(rates) = self.rates.as_ref().and_then(|rates| rates.get(idx)) {
+                    sub
+                        .set("p50", rates.p50)
+                        .set("p95", rates.p95)
+                        .set("p99", rates.p99);
+                }
             }
         }
-        printer.print(expander, MD);
+        if self.rates.is_some() {
+            printer.print(expander, MD_RATES);
+        } else {
+            printer.print(expander, MD);
+        }
     }
     pub fn total_hits(&self) -> u64 {
         self.bars.iter().map(|b| b.hits).sum()
@@ -2,5 +2,7 @@
 /// A trait for structs which hold the index of a date
 pub trait DateIndexed {
     fn date_idx(&self) -> usize;
+    fn hour(&self) -> u8;
     fn bytes(&self) -> u64;
+    fn visitor(&self) -> u64;
 }
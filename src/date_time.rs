@@ -103,6 +103,23 @@ impl DateTime {
             Self::new(year, month, day, hour, minute, second)
         }
     }
+    /// Return the number of seconds since 1970/01/01 00:00:00, in
+    /// the timezone of the log files
+    pub fn timestamp(self) -> i64 {
+        self.date.days_since_epoch() * 86400 + self.time.seconds() as i64
+    }
+    /// Build the date time from its number of seconds since 1970/01/01
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        let seconds = timestamp.rem_euclid(86400);
+        Self {
+            date: Date::from_days_since_epoch(timestamp.div_euclid(86400)),
+            time: Time {
+                hour: (seconds / 3600) as u8,
+                minute: (seconds / 60 % 60) as u8,
+                second: (seconds % 60) as u8,
+            },
+        }
+    }
     pub fn round_up(date: Date, time: Option<Time>) -> Self {
         Self {
             date,
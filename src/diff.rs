@@ -0,0 +1,229 @@
+use {
+    crate::*,
+    std::collections::HashMap,
+};
+
+/// Critical values of the chi-square distribution for p = 0.05,
+/// 0.01 and 0.001, per degree of freedom (from 1 to 5)
+static CHI_SQUARE_CRITICAL_VALUES: [[f64; 3]; 5] = [
+    [3.841, 6.635, 10.828],
+    [5.991, 9.210, 13.816],
+    [7.815, 11.345, 16.266],
+    [9.488, 13.277, 18.467],
+    [11.070, 15.086, 20.515],
+];
+
+/// The level of significance of a difference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Significance {
+    None,
+    P05,
+    P01,
+    P001,
+}
+
+impl Significance {
+    /// Assess the significance of a chi-square statistic
+    pub fn from_chi_square(chi2: f64, degrees_of_freedom: usize) -> Self {
+        if degrees_of_freedom == 0 {
+            return Self::None;
+        }
+        let critical = &CHI_SQUARE_CRITICAL_VALUES[degrees_of_freedom.min(5) - 1];
+        if chi2 >= critical[2] {
+            Self::P001
+        } else if chi2 >= critical[1] {
+            Self::P01
+        } else if chi2 >= critical[0] {
+            Self::P05
+        } else {
+            Self::None
+        }
+    }
+    /// The usual stars notation, eg `**` for p < 0.01
+    pub fn stars(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::P05 => "*",
+            Self::P01 => "**",
+            Self::P001 => "***",
+        }
+    }
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::None => "not significant",
+            Self::P05 => "p < 0.05",
+            Self::P01 => "p < 0.01",
+            Self::P001 => "p < 0.001",
+        }
+    }
+}
+
+/// Minimal expected count of a cell for the chi-square test
+/// to be reliable
+const MIN_EXPECTED_COUNT: f64 = 5.0;
+
+/// Compute the chi-square statistic of a contingency table with
+/// two columns (the log sets) and the degrees of freedom.
+///
+/// Rows whose expected counts are too small are pooled together.
+/// None is returned when the test can't be done, eg because there
+/// are too few hits.
+pub fn chi_square(rows: &[[u64; 2]]) -> Option<(f64, usize)> {
+    let col_totals = [
+        rows.iter().map(|row| row[0]).sum::<u64>() as f64,
+        rows.iter().map(|row| row[1]).sum::<u64>() as f64,
+    ];
+    let total = col_totals[0] + col_totals[1];
+    if col_totals[0] == 0.0 || col_totals[1] == 0.0 {
+        return None;
+    }
+    let min_col_total = col_totals[0].min(col_totals[1]);
+    let is_sparse = |row: &[u64; 2]| {
+        (row[0] + row[1]) as f64 * min_col_total / total < MIN_EXPECTED_COUNT
+    };
+    let mut pooled = [0, 0];
+    let mut kept: Vec<[u64; 2]> = Vec::new();
+    for row in rows {
+        if is_sparse(row) {
+            pooled[0] += row[0];
+            pooled[1] += row[1];
+        } else {
+            kept.push(*row);
+        }
+    }
+    if pooled[0] + pooled[1] > 0 {
+        // a pool still too small goes with the smallest row
+        let smallest = kept.iter_mut().min_by_key(|row| row[0] + row[1]);
+        match smallest {
+            Some(row) if is_sparse(&pooled) => {
+                row[0] += pooled[0];
+                row[1] += pooled[1];
+            }
+            _ => kept.push(pooled),
+        }
+    }
+    if kept.iter().any(is_sparse) {
+        return None;
+    }
+    if kept.len() < 2 {
+        return None;
+    }
+    let mut chi2 = 0.0;
+    for row in &kept {
+        let row_total = (row[0] + row[1]) as f64;
+        for col in 0..2 {
+            let expected = row_total * col_totals[col] / total;
+            let diff = row[col] as f64 - expected;
+            chi2 += diff * diff / expected;
+        }
+    }
+    Some((chi2, kept.len() - 1))
+}
+
+/// Test whether the proportions `a` of `n_a` and `b` of `n_b` differ
+pub fn proportion_significance(a: u64, n_a: u64, b: u64, n_b: u64) -> Significance {
+    // the chi-square test of the 2x2 table is equivalent
+    // to the two-proportion z-test
+    match chi_square(&[[a, b], [n_a - a, n_b - b]]) {
+        Some((chi2, df)) => Significance::from_chi_square(chi2, df),
+        None => Significance::None,
+    }
+}
+
+/// Counts of the hits of a path (or any group) in a log set
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffCounts {
+    pub hits: u64,
+    pub errors: u64,
+    pub bytes: u64,
+}
+
+impl DiffCounts {
+    pub fn add(&mut self, line: &LogLine) {
+        self.hits += 1;
+        if line.status >= 400 {
+            self.errors += 1;
+        }
+        self.bytes += line.bytes_sent;
+    }
+    pub fn error_rate(self) -> f64 {
+        if self.hits == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.hits as f64
+        }
+    }
+    pub fn bytes_per_hit(self) -> u64 {
+        self.bytes.checked_div(self.hits).unwrap_or(0)
+    }
+}
+
+/// The comparison of two log sets
+pub struct LogDiff<'b> {
+    pub bases: [&'b LogBase; 2],
+    pub totals: [DiffCounts; 2],
+    /// hits per status class (1xx to 5xx)
+    pub status_classes: [[u64; 2]; 5],
+    pub paths: HashMap<&'b str, [DiffCounts; 2]>,
+}
+
+impl<'b> LogDiff<'b> {
+    pub fn new(a: &'b LogBase, b: &'b LogBase, all_paths: bool) -> Self {
+        let bases = [a, b];
+        let mut totals = [DiffCounts::default(); 2];
+        let mut status_classes = [[0; 2]; 5];
+        let mut paths: HashMap<&str, [DiffCounts; 2]> = HashMap::new();
+        for (side, base) in bases.iter().enumerate() {
+            for line in &base.lines {
+                totals[side].add(line);
+                let class = (line.status / 100).clamp(1, 5) as usize - 1;
+                status_classes[class][side] += 1;
+                if all_paths || !line.is_resource() {
+                    paths.entry(&line.path).or_default()[side].add(line);
+                }
+            }
+        }
+        Self { bases, totals, status_classes, paths }
+    }
+    /// Return the chi-square statistic of the status classes and its
+    /// significance, or None when there are too few hits for the test
+    pub fn status_significance(&self) -> Option<(f64, Significance)> {
+        chi_square(&self.status_classes)
+            .map(|(chi2, df)| (chi2, Significance::from_chi_square(chi2, df)))
+    }
+    /// Return whether the error rate of the path significantly
+    /// differs between the log sets
+    pub fn error_significance(counts: &[DiffCounts; 2]) -> Significance {
+        proportion_significance(
+            counts[0].errors,
+            counts[0].hits,
+            counts[1].errors,
+            counts[1].hits,
+        )
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_chi_square() {
+        // same distribution, different volumes
+        let (chi2, df) = chi_square(&[[100, 200], [50, 100], [0, 0]]).unwrap();
+        assert_eq!(df, 1);
+        assert!(chi2 < 0.001);
+        assert_eq!(Significance::from_chi_square(chi2, df), Significance::None);
+        // many more errors in the first set
+        let significance = proportion_significance(30, 100, 10, 100);
+        assert_eq!(significance, Significance::P001);
+        let significance = proportion_significance(3, 10, 2, 10);
+        assert_eq!(significance, Significance::None);
+        // a few 1xx hits are pooled with the other small classes
+        let (_, df) = chi_square(&[[2, 0], [900, 950], [80, 40], [1, 3], [10, 5]]).unwrap();
+        assert_eq!(df, 2);
+        // too few hits for the test
+        assert_eq!(chi_square(&[[3, 1], [2, 4]]), None);
+    }
+}
@@ -0,0 +1,194 @@
+use {
+    crate::*,
+    std::{
+        collections::HashMap,
+        path::PathBuf,
+    },
+};
+
+/// A range of values of a distribution, with the number of items
+/// whose value is in the range, and the sum of those values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributionBucket {
+    /// the smallest value of the range
+    pub min: u64,
+    /// the greatest value of the range, included
+    pub max: u64,
+    pub count: u64,
+    pub sum: u64,
+}
+
+/// Return the index of the bucket of a value: 0 and 1 have their own
+/// buckets, then there's one bucket per power of ten (2-9, 10-99, etc.)
+fn bucket_idx(value: u64) -> usize {
+    match value {
+        0 => 0,
+        1 => 1,
+        _ => {
+            let mut idx = 2;
+            let mut rest = value / 10;
+            while rest > 0 {
+                idx += 1;
+                rest /= 10;
+            }
+            idx
+        }
+    }
+}
+
+fn bucket_bounds(idx: usize) -> (u64, u64) {
+    match idx {
+        0 => (0, 0),
+        1 => (1, 1),
+        2 => (2, 9),
+        _ => (10u64.pow(idx as u32 - 2), 10u64.saturating_pow(idx as u32 - 1) - 1),
+    }
+}
+
+/// The shape of the values of a dimension of the traffic, in
+/// log-scaled buckets
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    /// the key of the distribution in exports, eg `hits_per_ip`
+    pub key: &'static str,
+    /// what is distributed, eg `hits per IP`
+    pub title: &'static str,
+    /// the name of the counted items, eg `IPs`
+    pub items_name: &'static str,
+    /// the name of the values, eg `hits`
+    pub values_name: &'static str,
+    /// whether the values are sizes in bytes
+    pub bytes: bool,
+    /// the buckets from the one of the smallest value to the one of the
+    /// greatest value, intermediate buckets being present even when empty
+    pub buckets: Vec<DistributionBucket>,
+}
+
+impl Distribution {
+    pub fn new<I: IntoIterator<Item = u64>>(
+        key: &'static str,
+        title: &'static str,
+        items_name: &'static str,
+        values_name: &'static str,
+        bytes: bool,
+        values: I,
+    ) -> Self {
+        let mut buckets: Vec<DistributionBucket> = Vec::new();
+        for value in values {
+            let idx = bucket_idx(value);
+            while buckets.len() <= idx {
+                let (min, max) = bucket_bounds(buckets.len());
+                buckets.push(DistributionBucket { min, max, count: 0, sum: 0 });
+            }
+            buckets[idx].count += 1;
+            buckets[idx].sum += value;
+        }
+        let first = buckets.iter().position(|b| b.count > 0).unwrap_or(0);
+        buckets.drain(..first);
+        Self { key, title, items_name, values_name, bytes, buckets }
+    }
+    pub fn total_count(&self) -> u64 {
+        self.buckets.iter().map(|b| b.count).sum()
+    }
+    pub fn total_sum(&self) -> u64 {
+        self.buckets.iter().map(|b| b.sum).sum()
+    }
+}
+
+/// Compute the distributions of the hits per remote address, of the
+/// sizes of the responses, and of the hits per path (resources being
+/// excluded unless `all_paths` is true)
+pub fn compute_distributions(lines: &[LogLine], all_paths: bool) -> Vec<Distribution> {
+    let mut ip_hits: HashMap<&str, u64> = HashMap::new();
+    let mut path_hits: HashMap<&str, u64> = HashMap::new();
+    for line in lines {
+        *ip_hits.entry(&line.remote_addr).or_default() += 1;
+        if all_paths || !line.is_resource() {
+            *path_hits.entry(&line.path).or_default() += 1;
+        }
+    }
+    vec![
+        Distribution::new(
+            "hits_per_ip",
+            "hits per IP address",
+            "IP addresses",
+            "hits",
+            false,
+            ip_hits.into_values(),
+        ),
+        Distribution::new(
+            "bytes_per_response",
+            "bytes per response",
+            "responses",
+            "bytes",
+            true,
+            lines.iter().map(|line| line.bytes_sent),
+        ),
+        Distribution::new(
+            "hits_per_path",
+            "hits per path",
+            "paths",
+            "hits",
+            false,
+            path_hits.into_values(),
+        ),
+    ]
+}
+
+/// Print the distributions as a JSON object, for other tools
+pub fn print_distributions_json(
+    paths: &[PathBuf],
+    args: &args::Args,
+) -> Result<(), RhitError> {
+    let base = time!("LogBase::new", LogBase::new(paths, args))?;
+    let distributions = time!(compute_distributions(&base.lines, args.all));
+    println!("{{");
+    for (d_idx, distribution) in distributions.iter().enumerate() {
+        println!("  \"{}\": [", distribution.key);
+        for (b_idx, bucket) in distribution.buckets.iter().enumerate() {
+            println!(
+                r#"    {{"min": {}, "max": {}, "count": {}, "sum": {}}}{}"#,
+                bucket.min,
+                bucket.max,
+                bucket.count,
+                bucket.sum,
+                if b_idx + 1 < distribution.buckets.len() { "," } else { "" },
+            );
+        }
+        println!("  ]{}", if d_idx + 1 < distributions.len() { "," } else { "" });
+    }
+    println!("}}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod distributions_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(bucket_idx(0), 0);
+        assert_eq!(bucket_idx(1), 1);
+        assert_eq!(bucket_idx(9), 2);
+        assert_eq!(bucket_idx(10), 3);
+        assert_eq!(bucket_idx(999), 4);
+        assert_eq!(bucket_idx(1000), 5);
+        for idx in 0..20 {
+            let (min, max) = bucket_bounds(idx);
+            assert_eq!(bucket_idx(min), idx);
+            assert_eq!(bucket_idx(max), idx);
+        }
+    }
+
+    #[test]
+    fn test_distribution() {
+        let d = Distribution::new("d", "d", "items", "values", false, vec![1, 1, 1, 5, 150]);
+        assert_eq!(d.buckets.len(), 4); // 1, 2-9, 10-99, 100-999
+        assert_eq!(d.buckets[0], DistributionBucket { min: 1, max: 1, count: 3, sum: 3 });
+        assert_eq!(d.buckets[2].count, 0);
+        assert_eq!(d.buckets[3], DistributionBucket { min: 100, max: 999, count: 1, sum: 150 });
+        assert_eq!(d.total_count(), 5);
+        assert_eq!(d.total_sum(), 158);
+    }
+}
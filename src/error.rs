@@ -9,6 +9,32 @@ use {
 
 #[derive(Debug, Error)]
 pub enum RhitError {
+    #[error("Bot signature error: {0}")]
+    BotSignature(#[from] ParseBotSignatureError),
+    #[error("Configuration error: {0}")]
+    Config(#[from] ConfigError),
+    #[error("Exclusion profile error: {0}")]
+    ExclusionFilter(#[from] ParseExclusionFilterError),
+    #[error("Path template error: {0}")]
+    PathTemplate(#[from] PathTemplateError),
+    #[error("Scan rule error: {0}")]
+    ScanRule(#[from] ParseScanRuleError),
+    #[error("Tag rule error: {0}")]
+    TagRule(#[from] ParseTagRuleError),
+    #[error("Unknown tag {0:?}")]
+    UnknownTag(String),
+    #[error("rhit diff compares exactly two log sets, got {0}")]
+    DiffArity(usize),
+    #[error("Invalid comparison period {0:?}: a day or a range of days is expected")]
+    InvalidPeriod(String),
+    #[error("Invalid log line {0:?}: {1}")]
+    InvalidLogLine(String, ParseLogError),
+    #[error("Line {0} isn't a valid log line ({2}): {1:?}")]
+    InvalidLogLineAt(usize, String, ParseLogError),
+    #[error("Line {0} not found, there are only {1} lines")]
+    LineNotFound(usize, usize),
+    #[error("A blocklist needs at least one of --min-hits, --min-error-ratio or --min-rate")]
+    NoBlocklistThreshold,
     #[error("No hit found in {0:?}")]
     NoHitInPaths(Vec<PathBuf>),
     #[error("No log file found")]
@@ -17,11 +43,13 @@ pub enum RhitError {
     PathNotFound(PathBuf),
     #[error("IO error: {0:?}")]
     Io(#[from] io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
     #[error("Date time parsing error: {0:?}")]
     DateTime(#[from] ParseDateTimeError),
     #[error("status filter parsing error: {0:?}")]
     StatusFilter(#[from] ParseStatusFilterError),
-    #[error("String filter parsing error: {0:?}")]
+    #[error("String filter parsing error: {0}")]
     StrFilter(#[from] ParseStrFilterError),
     #[error("time filter parsing error: {0:?}")]
     TimeFilter(#[from] ParseTimeFilterError),
@@ -0,0 +1,94 @@
+use {
+    crate::*,
+    std::{
+        path::PathBuf,
+        str::FromStr,
+    },
+};
+
+/// A consumer keeping only the nth line, invalid lines
+/// being counted too so that n is the line number
+struct LineCatcher {
+    target: usize, // 1-based
+    count: usize,
+    caught: Option<LogLine>,
+    rejected: Option<(String, ParseLogError)>,
+}
+
+impl LineConsumer for LineCatcher {
+    fn eat_line(
+        &mut self,
+        log_line: LogLine,
+        _raw_line: &str,
+        _filtered_out: bool,
+    ) {
+        self.count += 1;
+        if self.count == self.target {
+            self.caught = Some(log_line);
+        }
+    }
+    fn reject_line(
+        &mut self,
+        raw_line: &str,
+        error: ParseLogError,
+    ) {
+        self.count += 1;
+        if self.count == self.target {
+            self.rejected = Some((raw_line.trim_end().to_owned(), error));
+        }
+    }
+}
+
+/// Explain the filters and, if asked, how they apply on a log line
+/// given either by its number or as a raw string
+pub fn print_explanations(
+    paths: &[PathBuf],
+    args: &args::Args,
+) -> Result<(), RhitError> {
+    let color = args.color();
+    let mut catcher = LineCatcher {
+        target: args.explain_line.unwrap_or(0),
+        count: 0,
+        caught: None,
+        rejected: None,
+    };
+    let mut file_reader = FileReader::new(paths, args, &mut catcher)?;
+    if args.explain_line.is_some() {
+        time!("reading files", file_reader.read_all_files())?;
+    }
+    let explained_line = match &args.explain {
+        Some(raw_line) => {
+            let mut line = LogLine::from_str(raw_line)
+                .map_err(|e| RhitError::InvalidLogLine(raw_line.to_owned(), e))?;
+            // there's no history, so only the user agent and the path are used
+            let mut bot_classifier = BotClassifier::new(args.bot_signatures.as_deref())?;
+            line.bot = bot_classifier.classify(&line);
+            let mut scanner_detector = ScannerDetector::new(args.scan_rules.as_deref())?;
+            let target = LogLine::request_target(raw_line).unwrap_or(&line.path);
+            line.scan = scanner_detector.detect(&line.remote_addr, target);
+            line.tags = file_reader.tagger().tag(&line);
+            Some(line)
+        }
+        None => None,
+    };
+    let tag_names = file_reader.tag_names();
+    let filterer = file_reader.filterer();
+    md::explain::print_filters(&filterer, color);
+    if let Some(line) = explained_line {
+        md::explain::print_line_explanation(&filterer, &line, &tag_names, color);
+    }
+    if let Some(n) = args.explain_line {
+        match (catcher.caught, catcher.rejected) {
+            (Some(line), _) => {
+                md::explain::print_line_explanation(&filterer, &line, &tag_names, color);
+            }
+            (None, Some((raw_line, error))) => {
+                return Err(RhitError::InvalidLogLineAt(n, raw_line, error));
+            }
+            (None, None) => {
+                return Err(RhitError::LineNotFound(n, catcher.count));
+            }
+        }
+    }
+    Ok(())
+}
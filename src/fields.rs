@@ -13,6 +13,21 @@ pub enum Field {
     Ip,
     Referers,
     Paths,
+    Bots,
+    PathTree,
+    RefererDomains,
+    RefererCategories,
+    Sessions,
+    EntryPages,
+    ExitPages,
+    Broken,
+    Security,
+    Anomalies,
+    Tags,
+    Heatmap,
+    Calendar,
+    Peaks,
+    Distributions,
 }
 
 pub static DEFAULT_FIELDS: &[Field] = &[
@@ -22,6 +37,7 @@ pub static DEFAULT_FIELDS: &[Field] = &[
     Field::Paths,
 ];
 
+/// the standard fields, the ones given by `-f all`
 pub static ALL_FIELDS: &[Field] = &[
     Field::Dates,
     Field::Times,
@@ -77,57 +93,89 @@ pub enum ParseFieldError {
     UnrecognizedFieldStart(char),
 }
 
+/// what a word of the fields argument designates
+enum FieldsToken {
+    All,
+    Field(Field),
+}
+
+fn parse_word(word: &str) -> Result<Option<FieldsToken>, ParseFieldError> {
+    let token = match word {
+        "tree" | "pathtree" => FieldsToken::Field(Field::PathTree),
+        "domains" | "refdomains" => FieldsToken::Field(Field::RefererDomains),
+        "sources" | "refsources" => FieldsToken::Field(Field::RefererCategories),
+        "sessions" => FieldsToken::Field(Field::Sessions),
+        "entries" => FieldsToken::Field(Field::EntryPages),
+        "exits" => FieldsToken::Field(Field::ExitPages),
+        "broken" | "404" => FieldsToken::Field(Field::Broken),
+        "security" | "scanners" => FieldsToken::Field(Field::Security),
+        "anomalies" => FieldsToken::Field(Field::Anomalies),
+        "tag" | "tags" => FieldsToken::Field(Field::Tags),
+        "heatmap" => FieldsToken::Field(Field::Heatmap),
+        "calendar" => FieldsToken::Field(Field::Calendar),
+        "peaks" => FieldsToken::Field(Field::Peaks),
+        "distributions" | "distrib" => FieldsToken::Field(Field::Distributions),
+        _ => {
+            // other fields are recognized by their initial
+            let c = match word.chars().find(|c| c.is_alphabetic()) {
+                Some(c) => c,
+                None => {
+                    return Ok(None);
+                }
+            };
+            match c {
+                'a' => FieldsToken::All,
+                'd' => FieldsToken::Field(Field::Dates),
+                't' => FieldsToken::Field(Field::Times),
+                's' => FieldsToken::Field(Field::Status),
+                'i' => FieldsToken::Field(Field::Ip),
+                'r' => FieldsToken::Field(Field::Referers),
+                'p' => FieldsToken::Field(Field::Paths),
+                'm' => FieldsToken::Field(Field::Methods),
+                'b' => FieldsToken::Field(Field::Bots),
+                _ => {
+                    return Err(ParseFieldError::UnrecognizedFieldStart(c));
+                }
+            }
+        }
+    };
+    Ok(Some(token))
+}
+
 impl FromStr for Fields {
     type Err = ParseFieldError;
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        //let additive = value.contains('+') || value.contains('-');
         let mut fields = if value.starts_with('+') || value.starts_with('-') {
             // if it starts with an addition or removal, the default set is implied
             Fields::default()
         } else {
             Fields::empty()
         };
-        let mut skip_alpha = false;
         let mut negative = false;
-        for c in value.chars() {
-            match c.to_ascii_lowercase() {
-                '+' | ' ' | ',' => {
-                    skip_alpha = false;
-                    negative = false;
-                }
-                '-' => {
-                    skip_alpha = false;
-                    negative = true;
-                }
-                'a' if !skip_alpha => {
-                    if negative {
-                        fields = Fields::empty();
-                    } else {
-                        fields = Fields::all();
-                    }
-                    skip_alpha = true;
-                }
-                c if !skip_alpha => {
-                    let field = match c {
-                        'd' => Field::Dates,
-                        't' => Field::Times,
-                        's' => Field::Status,
-                        'a'|'i' => Field::Ip,
-                        'r' => Field::Referers,
-                        'p' => Field::Paths,
-                        'm' => Field::Methods,
-                        _ => {
-                            return Err(ParseFieldError::UnrecognizedFieldStart(c));
+        let mut word = String::new();
+        // a final separator is added to flush the last word
+        for c in value.chars().chain(std::iter::once(',')) {
+            match c {
+                '+' | ' ' | ',' | '-' => {
+                    match parse_word(&word)? {
+                        Some(FieldsToken::All) => {
+                            fields = if negative { Fields::empty() } else { Fields::all() };
+                        }
+                        Some(FieldsToken::Field(field)) => {
+                            if negative {
+                                fields.remove(field);
+                            } else {
+                                fields.add(field);
+                            }
                         }
-                    };
-                    if negative {
-                        fields.remove(field);
-                    } else {
-                        fields.add(field);
+                        None => {}
                     }
-                    skip_alpha = true;
+                    word.clear();
+                    negative = c == '-';
+                }
+                c => {
+                    word.push(c.to_ascii_lowercase());
                 }
-                _ => {}
             }
         }
         Ok(fields)
@@ -185,6 +233,22 @@ mod fields_parsing_tests {
         );
     }
 
+    #[test]
+    fn parse_fields_by_name() {
+        assert_eq!(
+            Fields::from_str("tree,t").unwrap(),
+            Fields(vec![PathTree, Times]),
+        );
+        assert_eq!(
+            Fields::from_str("+tree-p").unwrap(),
+            Fields(vec![Dates, Status, Referers, PathTree]),
+        );
+        assert_eq!(
+            Fields::from_str("domains+sources+s").unwrap(),
+            Fields(vec![RefererDomains, RefererCategories, Status]),
+        );
+    }
+
     #[test]
     fn parse_fields_all() {
         assert_eq!(
@@ -0,0 +1,41 @@
+use {
+    crate::*,
+    clap::ValueEnum,
+};
+
+/// A filter on the bot classification of hits
+#[derive(ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotFilter {
+    /// Keep all hits
+    #[default]
+    Include,
+    /// Keep only the hits of bots
+    Only,
+    /// Remove the hits of bots
+    Exclude,
+}
+
+impl BotFilter {
+    pub fn accepts(self, bot: BotKind) -> bool {
+        match self {
+            Self::Include => true,
+            Self::Only => bot.is_bot(),
+            Self::Exclude => !bot.is_bot(),
+        }
+    }
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Include => "keep all hits",
+            Self::Only => "keep only the hits of bots",
+            Self::Exclude => "remove the hits of bots",
+        }
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Include => "include",
+            Self::Only => "only",
+            Self::Exclude => "exclude",
+        }
+    }
+}
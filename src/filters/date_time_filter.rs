@@ -1,5 +1,6 @@
 use {
     crate::*,
+    std::fmt,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -178,6 +179,22 @@ impl DateTimeFilter {
     }
 }
 
+impl fmt::Display for DateTimeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AfterDate(date) => write!(f, "after {}", date),
+            Self::AfterDateTime(datetime) => write!(f, "after {}", datetime),
+            Self::BeforeDate(date) => write!(f, "before {}", date),
+            Self::BeforeDateTime(datetime) => write!(f, "before {}", datetime),
+            Self::NotDate(date) => write!(f, "not on {}", date),
+            Self::NotDateTime(datetime) => write!(f, "not at {}", datetime),
+            Self::PreciseDate(date) => write!(f, "on {}", date),
+            Self::PreciseDateTime(datetime) => write!(f, "at {}", datetime),
+            Self::Range(a, b) => write!(f, "from {} to {}", a, b),
+        }
+    }
+}
+
 /// parse a numeric date with optionally implicit parts,
 /// and optionally a time
 fn parse_date_optional_time(
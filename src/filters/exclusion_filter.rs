@@ -0,0 +1,75 @@
+use {
+    crate::*,
+    itertools::Itertools,
+    lazy_regex::regex::{self, RegexSet},
+    std::fmt,
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum ParseExclusionFilterError {
+    #[error("invalid IP or CIDR {0:?} in profile {1:?}: {2}")]
+    InvalidCidr(String, String, ParseCidrError),
+    #[error("invalid pattern in profile {0:?}: {1}")]
+    InvalidRegex(String, regex::Error),
+}
+
+/// A filter removing the hits matching an exclusion profile
+#[derive(Debug)]
+pub struct ExclusionFilter {
+    cidrs: Vec<Cidr>,
+    paths: RegexSet,
+    user_agents: RegexSet,
+}
+
+impl ExclusionFilter {
+    pub fn new(
+        name: &str,
+        profile: &ExclusionProfile,
+    ) -> Result<Self, ParseExclusionFilterError> {
+        let cidrs = profile.ips
+            .iter()
+            .map(|s| {
+                s.parse().map_err(|e| {
+                    ParseExclusionFilterError::InvalidCidr(s.to_owned(), name.to_owned(), e)
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let invalid_regex = |e| ParseExclusionFilterError::InvalidRegex(name.to_owned(), e);
+        let paths = RegexSet::new(&profile.paths).map_err(invalid_regex)?;
+        let user_agents = RegexSet::new(&profile.user_agents).map_err(invalid_regex)?;
+        Ok(Self { cidrs, paths, user_agents })
+    }
+    pub fn accepts(&self, line: &LogLine) -> bool {
+        if self.paths.is_match(&line.path) {
+            return false;
+        }
+        if self.user_agents.is_match(line.user_agent) {
+            return false;
+        }
+        if !self.cidrs.is_empty() {
+            if let Ok(addr) = line.remote_addr.parse() {
+                if self.cidrs.iter().any(|cidr| cidr.contains(addr)) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Display for ExclusionFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.cidrs.is_empty() {
+            parts.push(format!("IP in {}", self.cidrs.iter().join(", ")));
+        }
+        if !self.paths.is_empty() {
+            parts.push(format!("path matching {}", self.paths.patterns().iter().join(", ")));
+        }
+        if !self.user_agents.is_empty() {
+            parts.push(format!("user agent matching {}", self.user_agents.patterns().iter().join(", ")));
+        }
+        write!(f, "remove hits with {}", parts.join(" or "))
+    }
+}
@@ -1,30 +1,84 @@
 use {
     crate::*,
+    itertools::Itertools,
+    smallvec::*,
+    std::fmt,
 };
 
-/// A filter for HTTP methods
-#[derive(Debug, Clone, Copy)]
+/// A filter for HTTP methods, allowing lists and exclusions
+/// Examples:
+///  `GET`
+///  `GET,POST`
+///  `!GET,!HEAD`
+///  `none`
+#[derive(Debug, Clone)]
 pub struct MethodFilter {
-    negative: bool,
-    method: Method,
+    include: SmallVec<[Method; 4]>,
+    exclude: SmallVec<[Method; 4]>,
 }
 
 impl MethodFilter {
-    pub fn from_string(mut pattern: &str) -> Self {
-        let negative = pattern.starts_with('!');
-        if negative {
-            pattern = &pattern[1..];
+    pub fn from_string(pattern: &str) -> Self {
+        let mut include = SmallVec::new();
+        let mut exclude = SmallVec::new();
+        for s in pattern.split(',') {
+            let s = s.trim();
+            if let Some(s) = s.strip_prefix('!') {
+                exclude.push(Method::from(s.trim()));
+            } else {
+                include.push(Method::from(s));
+            }
         }
-        let pattern = pattern.trim();
-        let method = Method::from(pattern);
-        Self { negative, method }
+        Self { include, exclude }
     }
-    pub fn contains(self, candidate: Method) -> bool {
-        if self.negative {
-            self.method != candidate
+    pub fn contains(&self, candidate: Method) -> bool {
+        if self.exclude.contains(&candidate) {
+            false
+        } else if self.include.is_empty() {
+            true
         } else {
-            self.method == candidate
+            self.include.contains(&candidate)
         }
     }
 }
 
+impl fmt::Display for MethodFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.include.is_empty() {
+            write!(f, "one of {}", self.include.iter().join(", "))?;
+        }
+        if !self.exclude.is_empty() {
+            if !self.include.is_empty() {
+                write!(f, " but ")?;
+            }
+            write!(f, "none of {}", self.exclude.iter().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod method_filter_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_method_filter() {
+        let mf = MethodFilter::from_string("GET");
+        assert_eq!(mf.contains(Method::Get), true);
+        assert_eq!(mf.contains(Method::Post), false);
+        let mf = MethodFilter::from_string("GET,POST");
+        assert_eq!(mf.contains(Method::Get), true);
+        assert_eq!(mf.contains(Method::Post), true);
+        assert_eq!(mf.contains(Method::Head), false);
+        let mf = MethodFilter::from_string("!GET, ! HEAD");
+        assert_eq!(mf.contains(Method::Get), false);
+        assert_eq!(mf.contains(Method::Head), false);
+        assert_eq!(mf.contains(Method::Put), true);
+        assert_eq!(mf.contains(Method::None), true);
+        let mf = MethodFilter::from_string("none");
+        assert_eq!(mf.contains(Method::None), true);
+        assert_eq!(mf.contains(Method::Get), false);
+    }
+}
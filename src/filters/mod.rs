@@ -1,15 +1,23 @@
 
+mod bot_filter;
 mod date_time_filter;
+mod exclusion_filter;
 mod status_filter;
 mod str_filter;
+mod str_matcher;
 mod method_filter;
+mod tag_filter;
 mod time_filter;
 
 pub use {
+    bot_filter::*,
     date_time_filter::*,
+    exclusion_filter::*,
     method_filter::*,
     status_filter::*,
     str_filter::*,
+    str_matcher::*,
+    tag_filter::*,
     time_filter::*,
 };
 
@@ -22,37 +30,71 @@ use {
 };
 
 pub enum Filter {
+    Bots(BotFilter),
     //Date(DateFilter),
     DateTime(DateTimeFilter),
+    Exclusion(ExclusionFilter),
     Ip(StrFilter),
     Method(MethodFilter),
     Path(StrFilter),
     Referer(StrFilter),
+    RefererDomain(StrFilter),
+    Scanners,
     Status(StatusFilter),
+    Tag(TagFilter),
     Time(TimeFilter),
 }
 
 impl Filter {
     pub fn accepts(&self, line: &LogLine) -> bool {
         match self {
+            Self::Bots(f) => f.accepts(line.bot),
             //Self::Date(f) => f.contains(line.date()),
             Self::DateTime(f) => f.contains(line.date_time),
+            Self::Exclusion(f) => f.accepts(line),
             Self::Ip(f) => f.accepts(&line.remote_addr),
             Self::Method(f) => f.contains(line.method),
             Self::Path(f) => f.accepts(&line.path),
             Self::Referer(f) => f.accepts(&line.referer),
+            Self::RefererDomain(f) => {
+                f.accepts(&lowercase_host(referer_host(&line.referer).unwrap_or("")))
+            }
+            Self::Scanners => !line.scan.is_scan(),
             Self::Status(f) => f.accepts(line.status),
+            Self::Tag(f) => f.accepts(line.tags),
             Self::Time(f) => f.contains(line.time()),
         }
     }
+    /// Return a readable description of the filter, maybe on several lines
+    pub fn explain(&self) -> String {
+        match self {
+            Self::Bots(f) => f.description().to_owned(),
+            Self::DateTime(f) => f.to_string(),
+            Self::Exclusion(f) => f.to_string(),
+            Self::Ip(f) => f.explain(),
+            Self::Method(f) => f.to_string(),
+            Self::Path(f) => f.explain(),
+            Self::Referer(f) => f.explain(),
+            Self::RefererDomain(f) => f.explain(),
+            Self::Scanners => "exclude the hits of vulnerability scanners".to_owned(),
+            Self::Status(f) => f.to_string(),
+            Self::Tag(f) => f.to_string(),
+            Self::Time(f) => f.to_string(),
+        }
+    }
     pub fn field_name(&self) -> &'static str {
         match self {
+            Self::Bots(_) => "bots",
             Self::DateTime(_) => "date", // it's date_time but clearer as date ?
+            Self::Exclusion(_) => "exclusion profile",
             Self::Ip(_) => "remote address",
             Self::Method(_) => "method",
             Self::Path(_) => "path",
             Self::Referer(_) => "referer", // it looks like it's the usual orthograph
+            Self::RefererDomain(_) => "referer domain",
+            Self::Scanners => "scanners",
             Self::Status(_) => "status",
+            Self::Tag(_) => "tag",
             Self::Time(_) => "time",
         }
     }
@@ -84,61 +126,109 @@ impl Filterer {
         args: &args::Args,
         first_date: Date,
         last_date: Date,
+        tagger: &Tagger,
     ) -> Result<Self, RhitError> {
         let (default_year, default_month) = unique_year_month(first_date, last_date);
         let mut filterings = SmallVec::new();
-        if let Some(s) = &args.date {
+        if !args.no_exclusions {
+            for (name, profile) in &args.config.exclusions {
+                filterings.push(Filtering::new(
+                    name,
+                    Filter::Exclusion(ExclusionFilter::new(name, profile)?),
+                ));
+            }
+        }
+        for s in &args.date {
             filterings.push(Filtering::new(
                 s,
                 Filter::DateTime(DateTimeFilter::new(s, default_year, default_month)?),
             ));
         }
-        if let Some(s) = &args.ip {
+        for s in &args.ip {
             filterings.push(Filtering::new(
                 s,
                 Filter::Ip(StrFilter::new(s)?),
             ));
         }
-        if let Some(s) = &args.method {
+        for s in &args.method {
             filterings.push(Filtering::new(
                 s,
                 Filter::Method(MethodFilter::from_string(s)),
             ));
         }
-        if let Some(s) = &args.path {
+        for s in &args.path {
             filterings.push(Filtering::new(
                 s,
                 Filter::Path(StrFilter::new(s)?),
             ));
         }
-        if let Some(s) = &args.referer {
+        for s in &args.referer {
             filterings.push(Filtering::new(
                 s,
                 Filter::Referer(StrFilter::new(s)?),
             ));
         }
-        if let Some(s) = &args.status {
+        for s in &args.referer_domain {
+            // host names are case insensitive, so both the pattern
+            // and the host are lowercased
+            filterings.push(Filtering::new(
+                s,
+                Filter::RefererDomain(StrFilter::new(&s.to_lowercase())?),
+            ));
+        }
+        for s in &args.status {
             filterings.push(Filtering::new(
                 s,
                 Filter::Status(StatusFilter::from_str(s)?),
             ));
         }
-        if let Some(s) = &args.time {
+        for s in &args.tag {
+            filterings.push(Filtering::new(
+                s,
+                Filter::Tag(TagFilter::new(s, tagger)?),
+            ));
+        }
+        for s in &args.time {
             filterings.push(Filtering::new(
                 s,
                 Filter::Time(TimeFilter::from_str(s)?),
             ));
         }
+        let bot_filter = if args.no_bots { BotFilter::Exclude } else { args.bots };
+        if bot_filter != BotFilter::Include {
+            filterings.push(Filtering::new(
+                bot_filter.name(),
+                Filter::Bots(bot_filter),
+            ));
+        }
+        if args.exclude_scanners {
+            filterings.push(Filtering::new(
+                "exclude",
+                Filter::Scanners,
+            ));
+        }
         Ok(Self { first_date, filterings })
     }
-    /// Return the filter used in rendering to select the day to display
-    pub fn date_filter(&self) -> Option<&DateTimeFilter> {
-        for i in 0..self.filterings.len() {
-            if let Filter::DateTime(f) = &self.filterings[i].filter {
-                return Some(f);
-            }
-        }
-        None
+    /// Return the filters used in rendering to select the days to display
+    pub fn date_filters(&self) -> impl Iterator<Item = &DateTimeFilter> {
+        self.filterings
+            .iter()
+            .filter_map(|filtering| match &filtering.filter {
+                Filter::DateTime(f) => Some(f),
+                _ => None,
+            })
+    }
+    pub fn has_date_filter(&self) -> bool {
+        self.date_filters().next().is_some()
+    }
+    pub fn has_time_filter(&self) -> bool {
+        self.filterings
+            .iter()
+            .any(|filtering| matches!(filtering.filter, Filter::Time(_)))
+    }
+    /// Tell whether the day may contain hits accepted by all date filters
+    pub fn overlaps(&self, date: Date) -> bool {
+        self.date_filters().all(|f| f.overlaps(date))
     }
     pub fn accepts(&mut self, line: &LogLine) -> bool {
         for filtering in &mut self.filterings {
@@ -1,6 +1,7 @@
 use {
     smallvec::*,
     std::{
+        fmt,
         num::ParseIntError,
         str::FromStr,
     },
@@ -66,6 +67,37 @@ impl StatusFilter {
     }
 }
 
+fn write_ranges(f: &mut fmt::Formatter<'_>, ranges: &[(u16, u16)]) -> fmt::Result {
+    for (i, range) in ranges.iter().enumerate() {
+        if i > 0 {
+            write!(f, " or ")?;
+        }
+        if range.0 == range.1 {
+            write!(f, "{}", range.0)?;
+        } else {
+            write!(f, "{}-{}", range.0, range.1)?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.include.is_empty() {
+            write!(f, "in ")?;
+            write_ranges(f, &self.include)?;
+        }
+        if !self.exclude.is_empty() {
+            if !self.include.is_empty() {
+                write!(f, " but ")?;
+            }
+            write!(f, "not in ")?;
+            write_ranges(f, &self.exclude)?;
+        }
+        Ok(())
+    }
+}
+
 impl FromStr for StatusFilter {
     type Err= ParseStatusFilterError;
     fn from_str(value: &str) -> Result<Self, ParseStatusFilterError> {
@@ -1,17 +1,33 @@
 use {
-    bet::BeTree,
-    lazy_regex::regex::{self, Regex},
+    crate::*,
+    bet::{BeTree, Child},
+    lazy_regex::regex,
+    std::fmt::Write,
     thiserror::Error,
 };
 
 #[derive(Debug, Error)]
 pub enum ParseStrFilterError {
 
-    #[error("invalid pattern {0:?} : {1}")]
-    InvalidPattern(String, String),
+    #[error("{reason}\n{}", show_position(.pattern, *.position))]
+    InvalidPattern {
+        pattern: String,
+        position: usize,
+        reason: String,
+    },
 
-    #[error("invalid regex {0:?}")]
-    InvalidRegex(#[from] regex::Error),
+    #[error("invalid regex\n{}\n{source}", show_position(.pattern, *.position))]
+    InvalidRegex {
+        pattern: String,
+        position: usize,
+        source: regex::Error,
+    },
+}
+
+/// Return the pattern with, on a second line, a caret under
+/// the char at the given position
+fn show_position(pattern: &str, position: usize) -> String {
+    format!("  {}\n  {:>width$}", pattern, '^', width = position + 1)
 }
 
 /// Query operators.
@@ -34,6 +50,7 @@ impl BoolOperator {
         }
     }
     /// tell whether we can skip evaluating the second operand
+    #[allow(clippy::match_like_matches_macro)]
     fn short_circuit(self, a: bool) -> bool {
         match (self, a) {
             (Self::And, false) => true,
@@ -41,16 +58,36 @@ impl BoolOperator {
             _ => false,
         }
     }
+    fn name(self) -> &'static str {
+        match self {
+            Self::And => "AND",
+            Self::Or => "OR",
+            Self::Not => "NOT",
+        }
+    }
 }
 
 /// a filter for strings
 #[derive(Debug)]
 pub struct StrFilter {
-    expr: BeTree<BoolOperator, Regex>,
+    expr: BeTree<BoolOperator, StrMatcher>,
 }
 
-fn invalid(pattern: &str, reason: &str) -> Result<StrFilter, ParseStrFilterError> {
-    Err(ParseStrFilterError::InvalidPattern(pattern.to_owned(), reason.to_owned()))
+fn invalid(pattern: &str, position: usize, reason: &str) -> Result<StrFilter, ParseStrFilterError> {
+    Err(ParseStrFilterError::InvalidPattern {
+        pattern: pattern.to_owned(),
+        position,
+        reason: reason.to_owned(),
+    })
+}
+
+/// compile the matcher of an atom starting at the given position
+fn atom_matcher(pattern: &str, position: usize, atom: &str) -> Result<StrMatcher, ParseStrFilterError> {
+    StrMatcher::new(atom).map_err(|source| ParseStrFilterError::InvalidRegex {
+        pattern: pattern.to_owned(),
+        position,
+        source,
+    })
 }
 
 impl StrFilter {
@@ -66,7 +103,8 @@ impl StrFilter {
     ///
     /// Example: ̀ dystroy & !miaou`
     pub fn with_be_syntax(pattern: &str) -> Result<Self, ParseStrFilterError> {
-        let mut expr = BeTree::new();
+        // atoms are built with the position of their first char
+        let mut expr: BeTree<BoolOperator, (usize, String)> = BeTree::new();
         let chars: Vec<char> = pattern.chars().collect();
         for i in 0..chars.len() {
             match chars[i] {
@@ -74,29 +112,28 @@ impl StrFilter {
                     if expr.accept_opening_par() {
                         expr.open_par();
                     } else {
-                        return invalid(pattern, "unexpected opening parenthesis");
+                        return invalid(pattern, i, "unexpected opening parenthesis");
                     }
                 }
                 ')' if i > 0 && chars.get(i - 1) == Some(&' ') => {
                     if expr.accept_closing_par() {
                         expr.close_par();
                     } else {
-                        println!("expr: {:#?}", &expr);
-                        return invalid(pattern, "unexpected closing parenthesis");
+                        return invalid(pattern, i, "unexpected closing parenthesis");
                     }
                 }
                 '&' if chars.get(i + 1) == Some(&' ') => {
                     if expr.accept_binary_operator() {
                         expr.push_operator(BoolOperator::And);
                     } else {
-                        return invalid(pattern, "unexpected '&'");
+                        return invalid(pattern, i, "unexpected '&'");
                     }
                 }
                 '|' if chars.get(i + 1) == Some(&' ') => {
                     if expr.accept_binary_operator() {
                         expr.push_operator(BoolOperator::Or);
                     } else {
-                        return invalid(pattern, "unexpected '|'");
+                        return invalid(pattern, i, "unexpected '|'");
                     }
                 }
                 '!' if expr.accept_unary_operator() => {
@@ -104,11 +141,14 @@ impl StrFilter {
                 }
                 ' ' => {}
                 c => {
-                    expr.mutate_or_create_atom(String::new).push(c);
+                    expr.mutate_or_create_atom(|| (i, String::new())).1.push(c);
                 }
             }
         }
-        let expr = expr.try_map_atoms(|s| Regex::new(s))?;
+        if expr.get_openness() > 0 {
+            return invalid(pattern, chars.len(), "missing closing parenthesis");
+        }
+        let expr = expr.try_map_atoms(|(position, s)| atom_matcher(pattern, *position, s))?;
         Ok(Self { expr })
     }
     /// parse a filter defined with the comma syntax, ie a AND on
@@ -117,27 +157,67 @@ impl StrFilter {
     /// Example: ̀ dystroy,!miaou`
     pub fn with_comma_syntax(pattern: &str) -> Result<Self, ParseStrFilterError> {
         let mut expr = BeTree::new();
-        let atoms = pattern.split(',').map(|s| s.trim());
-        for atom in atoms {
+        let mut position = 0; // in chars
+        for token in pattern.split(',') {
+            let token_position = position;
+            position += token.chars().count() + 1;
+            let atom = token.trim_start();
+            let atom_position = token_position + token.chars().count() - atom.chars().count();
+            let atom = atom.trim_end();
             if atom.is_empty() {
-                return invalid(pattern, "empty token");
+                return invalid(pattern, atom_position, "empty token");
             }
             if !expr.is_empty() {
                 expr.push_operator(BoolOperator::And);
             }
             if let Some(atom) = atom.strip_prefix('!') {
                 expr.push_operator(BoolOperator::Not);
-                expr.push_atom(Regex::new(atom)?);
+                expr.push_atom(atom_matcher(pattern, atom_position + 1, atom)?);
             } else {
-                expr.push_atom(Regex::new(atom)?);
+                expr.push_atom(atom_matcher(pattern, atom_position, atom)?);
             }
         }
         Ok(Self { expr })
     }
+    /// Return a readable multi-line representation of the parsed
+    /// expression, as a tree
+    pub fn explain(&self) -> String {
+        let mut s = String::new();
+        self.write_node(&mut s, self.expr.head(), 0);
+        s
+    }
+    fn write_node(&self, s: &mut String, node: &bet::Node<BoolOperator>, depth: usize) {
+        match node.operator {
+            Some(op) => {
+                let _ = writeln!(s, "{:indent$}{}", "", op.name(), indent = 2 * depth);
+                self.write_child(s, node.left, depth + 1);
+                self.write_child(s, node.right, depth + 1);
+            }
+            None => {
+                // a node without operator just wraps its child
+                self.write_child(s, node.left, depth);
+            }
+        }
+    }
+    fn write_child(&self, s: &mut String, child: Child, depth: usize) {
+        match child {
+            Child::None => {}
+            Child::Node(node_id) => {
+                if let Some(node) = self.expr.node(node_id) {
+                    self.write_node(s, node, depth);
+                }
+            }
+            Child::Atom(atom_id) => {
+                if let Some(matcher) = self.expr.atom(atom_id) {
+                    let _ = writeln!(s, "{:indent$}{}", "", matcher, indent = 2 * depth);
+                }
+            }
+        }
+    }
     pub fn accepts(&self, candidate: &str) -> bool {
         self.expr
             .eval(
-                |r| r.is_match(candidate),
+                |m| m.is_match(candidate),
                 |op, a, b| op.eval(a, b),
                 |op, &a| op.short_circuit(a),
             )
@@ -154,6 +234,42 @@ mod str_filter_tests {
 
     use super::*;
 
+    #[test]
+    fn test_error_position() {
+        match StrFilter::new("a & ) b") {
+            Err(ParseStrFilterError::InvalidPattern { position, .. }) => assert_eq!(position, 4),
+            _ => panic!("expected an invalid pattern error"),
+        }
+        match StrFilter::new("a & !( b | c[ )") {
+            Err(ParseStrFilterError::InvalidRegex { position, .. }) => assert_eq!(position, 11),
+            _ => panic!("expected an invalid regex error"),
+        }
+        match StrFilter::new("a, !b[") {
+            Err(ParseStrFilterError::InvalidRegex { position, .. }) => assert_eq!(position, 4),
+            _ => panic!("expected an invalid regex error"),
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let f = StrFilter::new("dystroy & !( miaou | blog )").unwrap();
+        assert_eq!(
+            f.explain(),
+            "AND\n  contains \"dystroy\"\n  NOT\n    OR\n      contains \"miaou\"\n      contains \"blog\"\n",
+        );
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let f = StrFilter::new("i:/blog & !g:*.png").unwrap();
+        assert_eq!(f.accepts("/Blog/a.html"), true);
+        assert_eq!(f.accepts("/BLOG/a.png"), false);
+        let f = StrFilter::new("!=/,!^/static").unwrap();
+        assert_eq!(f.accepts("/"), false);
+        assert_eq!(f.accepts("/static/a.css"), false);
+        assert_eq!(f.accepts("/blog"), true);
+    }
+
     #[test]
     fn test_comma() {
         let f = StrFilter::new("dystroy,!miaou").unwrap();
@@ -0,0 +1,170 @@
+use {
+    lazy_regex::regex::{self, Regex},
+    std::fmt,
+};
+
+/// The chars which make a pattern a regular expression
+/// rather than a literal
+static REGEX_CHARS: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$',
+];
+
+/// The atom of a string filter.
+///
+/// Its syntax is a regular expression, optionally prefixed with
+/// modifiers:
+/// - `i:` for a case insensitive match
+/// - `g:` for a shell-style glob (`*` and `?` jokers, whole string)
+/// - `=` for an exact literal (whole string)
+///
+/// Simple literals and `^` anchored literals don't need a regex
+/// and are matched faster.
+#[derive(Debug, Clone)]
+pub enum StrMatcher {
+    Contains(Box<str>),
+    Exact(Box<str>),
+    Prefix(Box<str>),
+    Regex(Regex),
+}
+
+fn is_literal(s: &str) -> bool {
+    !s.contains(REGEX_CHARS)
+}
+
+/// build a regex from a shell-style glob, where `*` matches any
+/// sequence of chars and `?` matches exactly one char
+fn glob_to_regex(glob: &str) -> String {
+    let mut r = String::from("^");
+    let mut in_class = false;
+    for c in glob.chars() {
+        match c {
+            '*' if !in_class => r.push_str(".*"),
+            '?' if !in_class => r.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                r.push('[');
+            }
+            '!' if in_class && r.ends_with('[') => r.push('^'),
+            ']' if in_class => {
+                in_class = false;
+                r.push(']');
+            }
+            c if in_class => r.push(c),
+            c => r.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    r.push('$');
+    r
+}
+
+impl StrMatcher {
+    pub fn new(atom: &str) -> Result<Self, regex::Error> {
+        let mut s = atom;
+        let mut case_insensitive = false;
+        let mut glob = false;
+        loop {
+            if let Some(rest) = s.strip_prefix("i:") {
+                case_insensitive = true;
+                s = rest;
+            } else if let Some(rest) = s.strip_prefix("g:") {
+                glob = true;
+                s = rest;
+            } else {
+                break;
+            }
+        }
+        let flags = if case_insensitive { "(?i)" } else { "" };
+        if glob {
+            return Ok(Self::Regex(Regex::new(&format!("{}{}", flags, glob_to_regex(s)))?));
+        }
+        if let Some(literal) = s.strip_prefix('=') {
+            return Ok(if case_insensitive {
+                Self::Regex(Regex::new(&format!("{}^{}$", flags, regex::escape(literal)))?)
+            } else {
+                Self::Exact(literal.into())
+            });
+        }
+        if case_insensitive {
+            return Ok(Self::Regex(Regex::new(&format!("{}{}", flags, s))?));
+        }
+        if let Some(literal) = s.strip_prefix('^') {
+            if is_literal(literal) {
+                return Ok(Self::Prefix(literal.into()));
+            }
+        }
+        if is_literal(s) {
+            return Ok(Self::Contains(s.into()));
+        }
+        Ok(Self::Regex(Regex::new(s)?))
+    }
+    #[inline(always)]
+    pub fn is_match(&self, candidate: &str) -> bool {
+        match self {
+            Self::Contains(s) => candidate.contains(&**s),
+            Self::Exact(s) => candidate == &**s,
+            Self::Prefix(s) => candidate.starts_with(&**s),
+            Self::Regex(r) => r.is_match(candidate),
+        }
+    }
+}
+
+impl fmt::Display for StrMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contains(s) => write!(f, "contains {:?}", s),
+            Self::Exact(s) => write!(f, "equals {:?}", s),
+            Self::Prefix(s) => write!(f, "starts with {:?}", s),
+            Self::Regex(r) => write!(f, "matches /{}/", r.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod str_matcher_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_str_matcher_kinds() {
+        assert!(matches!(StrMatcher::new("blog").unwrap(), StrMatcher::Contains(_)));
+        assert!(matches!(StrMatcher::new("^/blog").unwrap(), StrMatcher::Prefix(_)));
+        assert!(matches!(StrMatcher::new("=/blog").unwrap(), StrMatcher::Exact(_)));
+        assert!(matches!(StrMatcher::new("^/blog$").unwrap(), StrMatcher::Regex(_)));
+        assert!(matches!(StrMatcher::new("i:blog").unwrap(), StrMatcher::Regex(_)));
+    }
+
+    #[test]
+    fn test_str_matcher_modifiers() {
+        let m = StrMatcher::new("i:/Blog").unwrap();
+        assert_eq!(m.is_match("/blog/2024"), true);
+        assert_eq!(m.is_match("/BLOG"), true);
+        assert_eq!(m.is_match("/blag"), false);
+        let m = StrMatcher::new("=/index.html").unwrap();
+        assert_eq!(m.is_match("/index.html"), true);
+        assert_eq!(m.is_match("/index_html"), false);
+        assert_eq!(m.is_match("/a/index.html"), false);
+        let m = StrMatcher::new("i:=/Index.html").unwrap();
+        assert_eq!(m.is_match("/INDEX.HTML"), true);
+        assert_eq!(m.is_match("/INDEX.HTML/a"), false);
+        let m = StrMatcher::new("g:/blog/*.htm?").unwrap();
+        assert_eq!(m.is_match("/blog/2024/a.html"), true);
+        assert_eq!(m.is_match("/blog/a.htm"), false);
+        assert_eq!(m.is_match("/blog/a+b.htmx"), true);
+        assert_eq!(m.is_match("/x/blog/a.html"), false);
+        let m = StrMatcher::new("g:/img/[!a]*").unwrap();
+        assert_eq!(m.is_match("/img/b.png"), true);
+        assert_eq!(m.is_match("/img/a.png"), false);
+        let m = StrMatcher::new("i:g:*.PNG").unwrap();
+        assert_eq!(m.is_match("/img/b.png"), true);
+        let m = StrMatcher::new("^/blog").unwrap();
+        assert_eq!(m.is_match("/blog/2024"), true);
+        assert_eq!(m.is_match("/x/blog"), false);
+        // a modifier is matched literally when escaped
+        let m = StrMatcher::new(r"\=x").unwrap();
+        assert_eq!(m.is_match("/a=x"), true);
+        let m = StrMatcher::new("[i]:x").unwrap();
+        assert_eq!(m.is_match("/i:x"), true);
+        assert_eq!(m.is_match("/x"), false);
+    }
+}
@@ -0,0 +1,72 @@
+use {
+    crate::*,
+    itertools::Itertools,
+    std::fmt,
+};
+
+/// A filter on the tags given by the tag rules, allowing lists
+/// and exclusions
+/// Examples:
+///  `api`
+///  `api,feed`
+///  `!static`
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    include: TagSet,
+    exclude: TagSet,
+    include_names: Vec<&'static str>,
+    exclude_names: Vec<&'static str>,
+}
+
+impl TagFilter {
+    pub fn new(pattern: &str, tagger: &Tagger) -> Result<Self, RhitError> {
+        let mut filter = Self {
+            include: TagSet::default(),
+            exclude: TagSet::default(),
+            include_names: Vec::new(),
+            exclude_names: Vec::new(),
+        };
+        for s in pattern.split(',') {
+            let s = s.trim();
+            let (negated, name) = match s.strip_prefix('!') {
+                Some(name) => (true, name.trim()),
+                None => (false, s),
+            };
+            let id = tagger.id(name)
+                .ok_or_else(|| RhitError::UnknownTag(name.to_owned()))?;
+            let name = tagger.names()[id];
+            if negated {
+                filter.exclude.insert(id);
+                filter.exclude_names.push(name);
+            } else {
+                filter.include.insert(id);
+                filter.include_names.push(name);
+            }
+        }
+        Ok(filter)
+    }
+    pub fn accepts(&self, tags: TagSet) -> bool {
+        if tags.intersects(self.exclude) {
+            false
+        } else if self.include.is_empty() {
+            true
+        } else {
+            tags.intersects(self.include)
+        }
+    }
+}
+
+impl fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.include_names.is_empty() {
+            write!(f, "tagged {}", self.include_names.iter().join(" or "))?;
+        }
+        if !self.exclude_names.is_empty() {
+            if !self.include_names.is_empty() {
+                write!(f, " but ")?;
+            }
+            write!(f, "not tagged {}", self.exclude_names.iter().join(" or "))?;
+        }
+        Ok(())
+    }
+}
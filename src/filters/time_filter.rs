@@ -1,6 +1,7 @@
 use {
     crate::*,
     lazy_regex::*,
+    std::fmt,
     thiserror::Error,
 };
 
@@ -36,6 +37,16 @@ impl TimeFilter {
     }
 }
 
+impl fmt::Display for TimeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::After(time) => write!(f, "after {}", time),
+            Self::Before(time) => write!(f, "before {}", time),
+            Self::Range(a, b) => write!(f, "from {} to {}", a, b),
+        }
+    }
+}
+
 impl FromStr for TimeFilter {
     type Err= ParseTimeFilterError;
     fn from_str(s: &str) -> Result<Self, ParseTimeFilterError> {
@@ -0,0 +1,157 @@
+use {
+    crate::*,
+    termimad::minimad::OwningTemplateExpander,
+    std::collections::HashSet,
+};
+
+static MD: &str = r#"
+## ${title}
+|:-:|:-:|:-:
+|**${row-key}**|**${hours}**|**${key}**
+|:-|:-|-:
+${rows
+|${label}|*${cells}*|${total}
+}
+|-:
+*·* none, *░▒▓█* up to ${max}
+"#;
+
+/// The header of the cells, with a label every 6 hours
+static HOURS: &str = "0h\u{a0}\u{a0}\u{a0}\u{a0}6h\u{a0}\u{a0}\u{a0}\u{a0}12h\u{a0}\u{a0}\u{a0}18h\u{a0}\u{a0}\u{a0}";
+
+/// The maximal number of days shown as rows. Longer periods
+/// are summed by day of the week
+const MAX_DAY_ROWS: usize = 31;
+
+#[derive(Clone, Copy, Default)]
+struct Cell {
+    hits: u64,
+    bytes_sent: u64,
+    visitors: u64,
+}
+
+impl Cell {
+    fn value(self, key: Key) -> u64 {
+        key.pick(self.hits, self.bytes_sent, self.visitors)
+    }
+}
+
+struct Row {
+    label: String,
+    cells: [Cell; 24],
+    total: Cell,
+}
+
+impl Row {
+    fn new(label: String) -> Self {
+        Self {
+            label,
+            cells: [Cell::default(); 24],
+            total: Cell::default(),
+        }
+    }
+}
+
+/// A grid of the hits with the days (or the days of the week,
+/// on long periods) as rows and the hours as columns
+pub struct Heatmap {
+    by_weekday: bool,
+    rows: Vec<Row>,
+}
+
+impl Heatmap {
+
+    pub fn from(base: &LogBase) -> Self {
+        // days without any hit have a row too
+        let days: Vec<Date> = match (base.dates.first(), base.dates.last()) {
+            (Some(&first), Some(&last)) => {
+                (0..=last.days_since_epoch() - first.days_since_epoch())
+                    .map(|d| first.add_days(d))
+                    .filter(|&date| base.filterer.overlaps(date))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let by_weekday = days.len() > MAX_DAY_ROWS;
+        let mut rows: Vec<Row> = if by_weekday {
+            WEEKDAYS_3_LETTERS
+                .iter()
+                .map(|&name| Row::new(name.to_owned()))
+                .collect()
+        } else {
+            days.iter()
+                .map(|date| Row::new(date.to_string()))
+                .collect()
+        };
+        // the row of every date of the base, by date index
+        let row_indexes: Vec<Option<usize>> = base.dates
+            .iter()
+            .map(|date| {
+                if by_weekday {
+                    Some(date.weekday() as usize)
+                } else {
+                    days.binary_search(date).ok()
+                }
+            })
+            .collect();
+        let mut cell_visitors = HashSet::new();
+        let mut row_visitors = HashSet::new();
+        for line in &base.lines {
+            let row_idx = match row_indexes[line.date_idx] {
+                Some(row_idx) => row_idx,
+                None => continue,
+            };
+            let hour = line.hour() as usize;
+            let row = &mut rows[row_idx];
+            for cell in [&mut row.cells[hour], &mut row.total] {
+                cell.hits += 1;
+                cell.bytes_sent += line.bytes_sent;
+            }
+            if !base.visitors {
+                continue;
+            }
+            if cell_visitors.insert((row_idx, hour, line.visitor)) {
+                row.cells[hour].visitors += 1;
+            }
+            if row_visitors.insert((row_idx, line.visitor)) {
+                row.total.visitors += 1;
+            }
+        }
+        Self { by_weekday, rows }
+    }
+
+    pub fn print(
+        &self,
+        printer: &md::Printer,
+    ) {
+        let key = printer.key;
+        let max = self.rows
+            .iter()
+            .flat_map(|row| row.cells.iter())
+            .map(|cell| cell.value(key))
+            .max()
+            .unwrap_or(0);
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set("row-key", if self.by_weekday { "weekday" } else { "date" })
+            .set("hours", HOURS)
+            .set("key", key.name())
+            .set_md("max", printer.md_key_sum(max));
+        if self.by_weekday {
+            expander.set("title", format!("{} by day of the week and hour", key.name()));
+        } else {
+            expander.set("title", format!("{} by day and hour", key.name()));
+        }
+        for row in &self.rows {
+            let cells: String = row.cells
+                .iter()
+                .map(|cell| shade(cell.value(key), max))
+                .collect();
+            expander.sub("rows")
+                .set("label", &row.label)
+                .set("cells", cells)
+                .set_md("total", printer.md_key_sum(row.total.value(key)));
+        }
+        printer.print(expander, MD);
+    }
+}
@@ -3,6 +3,7 @@
 // See https://en.wikipedia.org/wiki/Block_Elements
 //static H_CHARS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
 static V_CHARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+static SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
 
 
 /// will panic if counts aren't consistent with the max or
@@ -27,3 +28,14 @@ pub fn histo_line(
     }
     h
 }
+
+/// Return the shade of a cell of a heatmap, `·` being
+/// kept for cells without any value
+pub fn shade(value: u64, max: u64) -> char {
+    if value == 0 || max == 0 {
+        SHADES[0]
+    } else {
+        let idx = (4.0 * (value as f32) / (max as f32)).ceil() as usize;
+        SHADES[idx.clamp(1, 4)]
+    }
+}
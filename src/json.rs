@@ -1,13 +1,14 @@
 use {
     crate::*,
+    itertools::Itertools,
     std::{
         path::PathBuf,
     },
 };
 
 /// A printer writing lines as a JSON array
-#[derive(Default)]
 struct JsonPrinter {
+    tag_names: Vec<&'static str>,
     written: usize,
 }
 
@@ -32,7 +33,8 @@ impl LineConsumer for JsonPrinter {
     "path": "{}",
     "status": "{}",
     "bytes_sent": {},
-    "referer": "{}"
+    "referer": "{}",
+    "tags": [{}]
   }}"#,
             line.date(),
             line.time(),
@@ -42,6 +44,7 @@ impl LineConsumer for JsonPrinter {
             line.status,
             line.bytes_sent,
             line.referer,
+            line.tags.ids().map(|id| json_string(self.tag_names[id])).join(", "),
         );
         self.written += 1;
     }
@@ -55,12 +58,30 @@ impl LineConsumer for JsonPrinter {
     }
 }
 
+/// Return the string as a JSON string literal, with quotes
+pub fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
 pub fn print_json_lines(
     path: &[PathBuf],
     args: &args::Args,
 ) -> Result<(), RhitError> {
-    let mut printer = JsonPrinter::default();
-    let mut file_reader = FileReader::new(path, args, &mut printer)?;
+    let tagger = Tagger::new(args.tag_rules.as_deref())?;
+    let tag_names = tagger.names().to_vec();
+    let mut printer = JsonPrinter { tag_names, written: 0 };
+    let mut file_reader = FileReader::with_tagger(path, args, tagger, &mut printer)?;
     time!("reading files", file_reader.read_all_files())?;
     Ok(())
 }
@@ -1,4 +1,5 @@
 use {
+    crate::*,
     std::str::FromStr,
     thiserror::Error,
 };
@@ -7,6 +8,7 @@ use {
 pub enum Key {
     Hits,
     Bytes,
+    Visitors,
 }
 
 impl Default for Key {
@@ -15,6 +17,32 @@ impl Default for Key {
     }
 }
 
+impl Key {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hits => "hits",
+            Self::Bytes => "bytes",
+            Self::Visitors => "visitors",
+        }
+    }
+    /// Return the sum of the key over the lines
+    pub fn sum(self, lines: &[&LogLine]) -> u64 {
+        match self {
+            Self::Hits => lines.len() as u64,
+            Self::Bytes => lines.iter().map(|line| line.bytes_sent).sum(),
+            Self::Visitors => count_visitors(lines.iter().copied()) as u64,
+        }
+    }
+    /// Return the value which is counted with this key
+    pub fn pick(self, hits: u64, bytes: u64, visitors: u64) -> u64 {
+        match self {
+            Self::Hits => hits,
+            Self::Bytes => bytes,
+            Self::Visitors => visitors,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ParseKeyError {
     #[error("unrecognized key {0:?}")]
@@ -27,6 +55,7 @@ impl FromStr for Key {
         match value.to_lowercase().as_ref() {
             "h" | "hit" | "hits" => Ok(Self::Hits),
             "b" | "byte" | "bytes"  => Ok(Self::Bytes),
+            "v" | "visitor" | "visitors"  => Ok(Self::Visitors),
             _ => Err(ParseKeyError::UnrecognizedKey(value.to_owned()))
         }
     }
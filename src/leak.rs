@@ -5,6 +5,10 @@ use {
             Layout,
             System,
         },
+        collections::HashSet,
+        fs,
+        io,
+        path::Path,
     },
 };
 
@@ -30,3 +34,33 @@ unsafe impl GlobalAlloc for LeakingAllocator {
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
     }
 }
+
+/// Read a file whose content is kept for the whole life of
+/// the application.
+///
+/// This is used for the optional rule and signature files,
+/// whose parsed names are shared as `&'static str`.
+pub fn read_leaked(path: &Path) -> io::Result<&'static str> {
+    Ok(Box::leak(fs::read_to_string(path)?.into_boxed_str()))
+}
+
+/// A set of strings kept for the whole life of the application,
+/// so that the many log lines with the same value (eg the same
+/// user agent) share one string instead of each owning a copy
+#[derive(Default)]
+pub struct Interner {
+    strings: HashSet<&'static str>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, s: &str) -> &'static str {
+        match self.strings.get(s) {
+            Some(&interned) => interned,
+            None => {
+                let interned: &'static str = Box::leak(s.into());
+                self.strings.insert(interned);
+                interned
+            }
+        }
+    }
+}
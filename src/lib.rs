@@ -1,26 +1,50 @@
 #[macro_use] extern crate cli_log;
 
+mod anomalies;
+mod blocklist;
+mod bots;
+mod bucket_histogram;
+mod calendar;
+mod cidr;
 mod cli;
+mod comparison;
+mod config;
 mod csv;
+mod custom_grouping;
 mod date;
 mod date_histogram;
 mod date_idx;
 mod date_time;
+mod diff;
+mod distributions;
 mod error;
+mod explain;
 mod fields;
 mod filters;
+mod heatmap;
 mod histo_line;
 mod json;
 mod key;
 mod leak;
+mod line_field;
 mod line_group;
 mod method;
 mod nginx_log;
+mod path_normalizer;
+mod path_tree;
+mod peaks;
 mod raw;
+mod referers;
+mod scanners;
+mod sessions;
+mod tags;
 mod time;
+mod time_bucket;
 mod time_histogram;
 mod trend;
 mod trend_computer;
+mod trend_windows;
+mod visitors;
 pub mod md;
 pub mod output;
 
@@ -28,25 +52,49 @@ pub mod output;
 static ALLOC: leak::LeakingAllocator = leak::LeakingAllocator::new();
 
 pub use {
+    anomalies::*,
+    blocklist::*,
+    bots::*,
+    bucket_histogram::*,
+    calendar::*,
+    cidr::*,
     cli::*,
+    comparison::*,
+    config::*,
     csv::*,
+    custom_grouping::*,
     date::*,
     date_histogram::*,
     date_idx::*,
     date_time::*,
+    diff::*,
+    distributions::*,
     error::*,
+    explain::*,
     fields::*,
     filters::*,
+    heatmap::*,
     histo_line::*,
     json::*,
     key::*,
+    line_field::*,
     line_group::*,
     method::*,
     nginx_log::*,
     output::*,
+    path_normalizer::*,
+    path_tree::*,
+    peaks::*,
     raw::*,
+    referers::*,
+    scanners::*,
+    sessions::*,
+    tags::*,
     time::*,
+    time_bucket::*,
     time_histogram::*,
     trend::*,
     trend_computer::*,
+    trend_windows::*,
+    visitors::*,
 };
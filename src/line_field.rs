@@ -0,0 +1,202 @@
+use {
+    crate::*,
+    std::{
+        borrow::Cow,
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// A characteristic of log lines on which they can be grouped,
+/// in cross tables, drill downs and custom groupings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineField {
+    Date,
+    Hour,
+    Method,
+    Status,
+    Ip,
+    Referer,
+    Path,
+    Bot,
+    RefererDomain,
+    RefererSource,
+    UserAgent,
+    Tags,
+}
+
+#[derive(Debug, Error)]
+pub enum ParseLineFieldError {
+    #[error("unrecognized field {0:?}")]
+    UnrecognizedField(String),
+}
+
+impl FromStr for LineField {
+    type Err = ParseLineFieldError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "d" | "date" | "dates" => Ok(Self::Date),
+            "t" | "time" | "hour" | "hours" => Ok(Self::Hour),
+            "m" | "method" | "methods" => Ok(Self::Method),
+            "s" | "status" => Ok(Self::Status),
+            "i" | "ip" | "ips" => Ok(Self::Ip),
+            "r" | "ref" | "referer" | "referers" | "referrer" | "referrers" => Ok(Self::Referer),
+            "p" | "path" | "paths" => Ok(Self::Path),
+            "b" | "bot" | "bots" => Ok(Self::Bot),
+            "domain" | "domains" => Ok(Self::RefererDomain),
+            "source" | "sources" => Ok(Self::RefererSource),
+            "ua" | "agent" | "agents" | "user-agent" => Ok(Self::UserAgent),
+            "tag" | "tags" => Ok(Self::Tags),
+            _ => Err(ParseLineFieldError::UnrecognizedField(s.to_owned())),
+        }
+    }
+}
+
+impl LineField {
+    /// The name of the field, as a column header
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Hour => "hour",
+            Self::Method => "method",
+            Self::Status => "status",
+            Self::Ip => "IP address",
+            Self::Referer => "referrer",
+            Self::Path => "path",
+            Self::Bot => "bot",
+            Self::RefererDomain => "domain",
+            Self::RefererSource => "source",
+            Self::UserAgent => "user agent",
+            Self::Tags => "tags",
+        }
+    }
+    /// The name of the values of the field, as a table title
+    pub fn plural_name(self) -> &'static str {
+        match self {
+            Self::Date => "dates",
+            Self::Hour => "hours",
+            Self::Method => "methods",
+            Self::Status => "HTTP status codes",
+            Self::Ip => "remote IP addresses",
+            Self::Referer => "referrers",
+            Self::Path => "paths",
+            Self::Bot => "bots",
+            Self::RefererDomain => "referrer domains",
+            Self::RefererSource => "referrer sources",
+            Self::UserAgent => "user agents",
+            Self::Tags => "tag combinations",
+        }
+    }
+    /// Tell whether the line is counted in the tables of this
+    /// field (eg resources are excluded from paths, unless `--all`)
+    pub fn accepts(self, line: &LogLine, printer: &md::Printer) -> bool {
+        match self {
+            Self::Path => printer.all_paths || !line.is_resource(),
+            Self::Referer => line.referer.len() > 1,
+            Self::Bot => line.bot.is_bot(),
+            Self::RefererDomain => referer_host(&line.referer).map_or(false, |host| {
+                printer.referer_classifier.classify_host(host) != RefererCategory::Internal
+            }),
+            _ => true,
+        }
+    }
+    /// Return the value of the field for this line
+    pub fn value<'l>(self, line: &'l LogLine, printer: &md::Printer) -> Cow<'l, str> {
+        match self {
+            Self::Date => line.date().to_string().into(),
+            Self::Hour => format!("{:0>2}h", line.time().hour).into(),
+            Self::Method => line.method.to_string().into(),
+            Self::Status => line.status.to_string().into(),
+            Self::Ip => Cow::Borrowed(&line.remote_addr),
+            Self::Referer => Cow::Borrowed(&line.referer),
+            Self::Path => Cow::Borrowed(&line.path),
+            Self::Bot => line.bot.to_string().into(),
+            Self::RefererDomain => match referer_host(&line.referer).map(lowercase_host) {
+                Some(Cow::Borrowed(host)) if printer.registrable_domains => {
+                    registrable_domain(host).into()
+                }
+                Some(Cow::Owned(host)) if printer.registrable_domains => {
+                    registrable_domain(&host).to_owned().into()
+                }
+                Some(host) => host,
+                None => "-".into(),
+            },
+            Self::RefererSource => printer.referer_classifier.classify(&line.referer).to_string().into(),
+            Self::UserAgent => Cow::Borrowed(line.user_agent),
+            Self::Tags if line.tags.is_empty() => "-".into(),
+            Self::Tags => line.tags.join(&printer.tag_names, ", ").into(),
+        }
+    }
+}
+
+/// Two fields whose values are crossed, in a table whose rows
+/// are the values of the first field and columns the values of
+/// the second one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossFields {
+    pub rows: LineField,
+    pub cols: LineField,
+}
+
+impl FromStr for CrossFields {
+    type Err = ParseLineFieldError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(',') {
+            Some((rows, cols)) => Ok(Self {
+                rows: rows.parse()?,
+                cols: cols.parse()?,
+            }),
+            None => Err(ParseLineFieldError::UnrecognizedField(s.to_owned())),
+        }
+    }
+}
+
+/// A field whose groups are detailed with the most frequent
+/// values of other fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrillFields {
+    pub parent: LineField,
+    pub children: Vec<LineField>,
+}
+
+impl FromStr for DrillFields {
+    type Err = ParseLineFieldError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((parent, children)) => Ok(Self {
+                parent: parent.parse()?,
+                children: children
+                    .split(',')
+                    .map(|child| child.parse())
+                    .collect::<Result<_, _>>()?,
+            }),
+            None => Err(ParseLineFieldError::UnrecognizedField(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod line_field_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_cross_fields() {
+        let cross: CrossFields = "paths,status".parse().unwrap();
+        assert_eq!(cross.rows, LineField::Path);
+        assert_eq!(cross.cols, LineField::Status);
+        let cross: CrossFields = "i, m".parse().unwrap();
+        assert_eq!(cross.rows, LineField::Ip);
+        assert_eq!(cross.cols, LineField::Method);
+        assert!("path".parse::<CrossFields>().is_err());
+        assert!("path,color".parse::<CrossFields>().is_err());
+    }
+
+    #[test]
+    fn test_parse_drill_fields() {
+        let drill: DrillFields = "paths:referers,ip".parse().unwrap();
+        assert_eq!(drill.parent, LineField::Path);
+        assert_eq!(drill.children, vec![LineField::Referer, LineField::Ip]);
+        assert!("paths".parse::<DrillFields>().is_err());
+    }
+}
@@ -14,7 +14,7 @@ where
 {
     pub value: T,
     pub lines: Vec<&'b LogLine>, // guaranteed not empty
-    pub trend: Trend,
+    pub trend: Option<Trend>,
     pub bytes: u64,
     pub key_sum: u64,
 }
@@ -28,17 +28,27 @@ where
         lines: Vec<&'b LogLine>,
         trend_computer: &TrendComputer,
     ) -> Self {
-        debug_assert!(!lines.is_empty());
         let trend = trend_computer.compute_trend(&lines);
+        let mut group = Self::without_trend(value, lines, trend_computer.key);
+        group.trend = Some(trend);
+        group
+    }
+    pub fn without_trend(
+        value: T,
+        lines: Vec<&'b LogLine>,
+        key: Key,
+    ) -> Self {
+        debug_assert!(!lines.is_empty());
         let bytes = lines.iter().map(|ll| ll.bytes_sent).sum();
-        let key_sum = match trend_computer.key {
+        let key_sum = match key {
             Key::Hits => lines.len() as u64,
             Key::Bytes => bytes,
+            Key::Visitors => count_visitors(lines.iter().copied()) as u64,
         };
         Self {
             value,
             lines,
-            trend,
+            trend: None,
             bytes,
             key_sum,
         }
@@ -50,10 +60,6 @@ where
         self.lines.len()
     }
     pub fn histo_line(&self) -> String {
-        histo_line(
-            &self.trend.sum_per_day,
-            self.trend.max_day_count(),
-            false,
-        )
+        self.trend.as_ref().map_or_else(String::new, Trend::histo_line)
     }
 }
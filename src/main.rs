@@ -1,8 +1,12 @@
 #[macro_use] extern crate cli_log;
 
-fn main() -> Result<(), rhit::RhitError> {
+fn main() {
     init_cli_log!();
-    rhit::run()?;
+    if let Err(e) = rhit::run() {
+        // errors are displayed, not debugged, so that messages
+        // on several lines (eg parsing errors) are readable
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
     info!("bye");
-    Ok(())
 }
@@ -0,0 +1,68 @@
+use {
+    super::*,
+    crate::*,
+    num_format::{Locale, ToFormattedString},
+    termimad::minimad::OwningTemplateExpander,
+};
+
+static MD: &str = r#"
+## ${count} anomalies (${granularity}, threshold ${threshold})
+${limited
+${limit} most significant:
+}
+|:-:|:-:|:-:|:-|:-:|:-:|:-:|:-:
+|**${bucket-name}**|**kind**|**dimension**|**value**|**hits**|**usual**|**change**|**score**
+|:-:|:-:|:-:|:-|-:|-:|-:|-:
+${anomalies
+|${bucket}|${kind}|${dimension}|${value}|${hits}|${baseline}|${magnitude}|${score}
+}
+|-:
+"#;
+
+/// Print the days (or hours) where a path, an IP, a referrer or a
+/// status had significantly more or less hits than usual
+pub fn print_anomalies(
+    base: &LogBase,
+    printer: &Printer,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let detector = AnomalyDetector::new(base, printer.anomaly_threshold);
+    let anomalies = time!(detector.detect(printer.all_paths));
+    if anomalies.is_empty() {
+        println!(
+            "No anomaly found with a threshold of {}",
+            printer.anomaly_threshold,
+        );
+        return;
+    }
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("count", anomalies.len().to_formatted_string(&Locale::en))
+        .set("threshold", printer.anomaly_threshold)
+        .set("granularity", if detector.is_hourly() { "hourly" } else { "daily" })
+        .set("bucket-name", if detector.is_hourly() { "hour" } else { "day" });
+    if anomalies.len() > limit {
+        expander.sub("limited").set("limit", limit);
+    }
+    for anomaly in anomalies.iter().take(limit) {
+        let kind = match anomaly.kind() {
+            AnomalyKind::Spike => "**spike**",
+            AnomalyKind::Drop => "*drop*",
+        };
+        expander.sub("anomalies")
+            .set("bucket", anomaly.bucket)
+            .set_md("kind", kind)
+            .set("dimension", anomaly.dimension)
+            .set("value", &anomaly.value)
+            .set_md("hits", printer.md_hits(anomaly.count as usize))
+            .set("baseline", format!("{:.0}", anomaly.baseline))
+            .set("magnitude", anomaly.magnitude())
+            .set("score", format!("{:+.1}", anomaly.score));
+    }
+    printer.print(expander, MD);
+}
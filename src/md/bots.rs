@@ -0,0 +1,29 @@
+use {
+    super::*,
+    crate::*,
+};
+
+pub fn print_bots(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let section = Section {
+        groups_name: "bots",
+        group_key: "bot",
+        view: View::Limited(limit),
+        changes: true,
+    };
+    printer.print_groups(
+        &section,
+        log_lines,
+        |line| line.bot.is_bot(),
+        |line| line.bot,
+        trend_computer,
+    );
+}
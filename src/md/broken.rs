@@ -0,0 +1,148 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    num_format::{Locale, ToFormattedString},
+    std::cmp::Reverse,
+    termimad::minimad::{OwningSubTemplateExpander, OwningTemplateExpander},
+};
+
+static MD_BROKEN_NO_TRENDS: &str = r#"
+## ${groups-count} broken paths (4xx). ${limited}
+|:-:|:-|:-:|:-:|:-:|:-:
+|**#**|**path / referrer**|**status**|**hits**|**first seen**|**last seen**
+|-:|:-|:-:|-:|:-:|:-:
+${rows
+|${idx}|**${path}**${arrow}${referer}${referer-note}|${status}|${hits}|${first}|${last}
+}
+|-:
+"#;
+
+static MD_BROKEN_TRENDS: &str = r#"
+## ${groups-count} broken paths (4xx). ${limited}
+|:-:|:-|:-:|:-:|:-:|:-:|:-:|:-:
+|**#**|**path / referrer**|**status**|**hits**|**first seen**|**last seen**|**${histo-name}**|**trend**
+|-:|:-|:-:|-:|:-:|:-:|-:|:-:
+${rows
+|${idx}|**${path}**${arrow}${referer}${referer-note}|${status}|${hits}|${first}|${last}|*${histo-line}*|${trend}
+}
+|-:
+"#;
+
+/// Set the hits, dates and trend of a row
+fn set_stats(
+    sub: &mut OwningSubTemplateExpander,
+    lines: &[&LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    sub.set_md("hits", printer.md_hits(lines.len()))
+        .set("first", lines[0].date())
+        .set("last", lines[lines.len() - 1].date());
+    if let Some(trend_computer) = trend_computer {
+        let trend = trend_computer.compute_trend(lines);
+        sub.set("histo-line", trend.histo_line());
+        if lines.len() > 9 {
+            sub.set_md("trend", trend.markdown());
+        }
+    }
+}
+
+/// Print the paths answered with a 4xx status, each one with the
+/// referers linking to it, the internal ones first as they're our
+/// own broken links
+pub fn print_broken(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let referers_limit = match printer.detail_level {
+        0 => 2,
+        1 => 3,
+        l => l * 3,
+    };
+    let classifier = &printer.referer_classifier;
+    let groups = log_lines
+        .iter()
+        .filter(|line| (400..500).contains(&line.status))
+        .into_group_map_by(|line| &line.path);
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("histo-name", printer.trend_bucket.plural_name())
+        .set("groups-count", groups.len().to_formatted_string(&Locale::en));
+    if groups.len() > limit {
+        expander.set("limited", format!("{} most frequent:", limit));
+    }
+    groups
+        .into_iter()
+        .map(|(path, lines)| {
+            let key_sum = printer.key.pick(
+                lines.len() as u64,
+                lines.iter().map(|line| line.bytes_sent).sum(),
+                count_visitors(lines.iter().copied()) as u64,
+            );
+            (path, lines, key_sum)
+        })
+        .sorted_unstable_by_key(|(_, _, key_sum)| Reverse(*key_sum))
+        .take(limit)
+        .enumerate()
+        .for_each(|(idx, (path, lines, _))| {
+            let status = lines
+                .iter()
+                .map(|line| line.status)
+                .unique()
+                .sorted()
+                .join(",");
+            let sub = expander.sub("rows");
+            sub.set("idx", idx + 1)
+                .set("path", path)
+                .set("status", status);
+            set_stats(sub, &lines, printer, trend_computer);
+            lines
+                .iter()
+                .copied()
+                .into_group_map_by(|line| &line.referer)
+                .into_iter()
+                .map(|(referer, lines)| {
+                    let category = classifier.classify(referer);
+                    (referer, category, lines)
+                })
+                .sorted_unstable_by_key(|(_, category, lines)| {
+                    (*category != RefererCategory::Internal, Reverse(lines.len()))
+                })
+                .take(referers_limit)
+                .for_each(|(referer, category, lines)| {
+                    // the scheme is removed to save some width
+                    let short = referer
+                        .split_once("://")
+                        .map_or(&**referer, |(_, rest)| rest);
+                    let sub = expander.sub("rows");
+                    sub.set("arrow", "\u{a0}\u{a0}↳ ");
+                    match category {
+                        RefererCategory::Direct => {
+                            sub.set_md("referer-note", "*no referer*");
+                        }
+                        RefererCategory::Internal => {
+                            sub.set("referer", short)
+                                .set_md("referer-note", " *(internal)*");
+                        }
+                        _ => {
+                            sub.set("referer", short);
+                        }
+                    }
+                    set_stats(sub, &lines, printer, trend_computer);
+                });
+        });
+    let template = if trend_computer.is_some() {
+        MD_BROKEN_TRENDS
+    } else {
+        MD_BROKEN_NO_TRENDS
+    };
+    printer.print(expander, template);
+}
@@ -0,0 +1,67 @@
+use {
+    super::*,
+    crate::*,
+    termimad::minimad::OwningTemplateExpander,
+};
+
+static MD: &str = r#"
+## comparison of periods
+|:-:|:-:|:-:|:-:|:-:|:-:|:-:
+|**period**|**dates**|**days**|**hits**|**hits per day**|**visitors**|**bytes**
+|:-:|:-:|-:|-:|-:|-:|-:
+${periods
+|**${name}**|${dates}|${days}|${hits}|${hits-per-day}|${visitors}|${bytes}
+}
+|:-:|:-:|-:|-:|-:|-:|-:
+|**Δ**||||${delta-hits-per-day}|${delta-visitors}|${delta-bytes}
+|-:
+"#;
+
+/// Print the totals of both periods of the comparison.
+///
+/// Hits per day are computed over the days of the periods which
+/// are covered by the logs, as a period may start before them.
+pub fn print_periods(
+    base: &LogBase,
+    printer: &Printer,
+    comparison: &Comparison,
+) {
+    let mut sides: [Vec<&LogLine>; 2] = Default::default();
+    for line in &base.lines {
+        if let Some(side) = comparison.side(line.date()) {
+            sides[side].push(line);
+        }
+    }
+    let mut expander = OwningTemplateExpander::new();
+    let mut hits_per_day = [0.0; 2];
+    let mut visitors = [0; 2];
+    let mut bytes = [0; 2];
+    for (side, period) in [comparison.a, comparison.b].iter().enumerate() {
+        let lines = &sides[side];
+        let days = period.covered_days(base.start_time(), base.end_time());
+        if days > 0 {
+            hits_per_day[side] = lines.len() as f64 / days as f64;
+        }
+        if printer.visitors {
+            visitors[side] = count_visitors(lines.iter().copied());
+        }
+        bytes[side] = lines.iter().map(|line| line.bytes_sent).sum();
+        expander.sub("periods")
+            .set("name", if side == 0 { "A" } else { "B" })
+            .set("dates", period)
+            .set("days", if days < period.day_count() {
+                format!("{} of {}", days, period.day_count())
+            } else {
+                days.to_string()
+            })
+            .set_md("hits", printer.md_hits(lines.len()))
+            .set("hits-per-day", format!("{:.0}", hits_per_day[side]))
+            .set_md("visitors", printer.md_visitors(visitors[side]))
+            .set_md("bytes", printer.md_bytes(bytes[side]));
+    }
+    expander
+        .set("delta-hits-per-day", percent_change(hits_per_day[0], hits_per_day[1]))
+        .set("delta-visitors", percent_change(visitors[0] as f64, visitors[1] as f64))
+        .set("delta-bytes", percent_change(bytes[0] as f64, bytes[1] as f64));
+    printer.print(expander, MD);
+}
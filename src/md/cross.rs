@@ -0,0 +1,134 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    num_format::{Locale, ToFormattedString},
+    std::{
+        borrow::Cow,
+        cmp::Reverse,
+    },
+    termimad::minimad::OwningTemplateExpander,
+};
+
+/// Build the template of a cross table, which depends on
+/// the number of columns.
+///
+/// Column values aren't written in the template, which would break
+/// on `|` or `${`, but set in the `h0`, `h1`... placeholders
+fn template(shown_col_count: usize, with_other: bool) -> String {
+    let col_count = shown_col_count + with_other as usize + 1;
+    let mut md = String::from("\n## ${title}\n|:-:|:-|");
+    md.push_str(&":-:|".repeat(col_count));
+    md.push_str("\n|**#**|**${row-key}**|");
+    for idx in 0..shown_col_count {
+        md.push_str(&format!("**${{h{}}}**|", idx));
+    }
+    if with_other {
+        md.push_str("**other**|");
+    }
+    md.push_str("**total**\n|-:|:-|");
+    md.push_str(&"-:|".repeat(col_count));
+    md.push_str("\n${rows\n|${idx}|${row-value}|");
+    for idx in 0..shown_col_count {
+        md.push_str(&format!("${{c{}}}|", idx));
+    }
+    if with_other {
+        md.push_str("${other}|");
+    }
+    md.push_str("${total}\n}\n|-:\n");
+    md
+}
+
+/// Print a table whose rows are the most frequent values of a field,
+/// and columns the most frequent values of another field, each cell
+/// showing the key and its part in the row
+pub fn print_cross(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    cross: CrossFields,
+) {
+    let (row_limit, col_limit) = match printer.detail_level {
+        0 => (5, 4),
+        1 => (10, 6),
+        l => (l * 20, 6 + l),
+    };
+    let key = printer.key;
+    let lines: Vec<&LogLine> = log_lines
+        .iter()
+        .filter(|line| cross.rows.accepts(line, printer) && cross.cols.accepts(line, printer))
+        .collect();
+    let cols: Vec<Cow<str>> = lines
+        .iter()
+        .copied()
+        .into_group_map_by(|line| cross.cols.value(line, printer))
+        .into_iter()
+        .map(|(value, lines)| (value, key.sum(&lines)))
+        .sorted_unstable_by_key(|(value, sum)| (Reverse(*sum), value.clone()))
+        .map(|(value, _)| value)
+        .collect();
+    let shown_cols = &cols[..cols.len().min(col_limit)];
+    let with_other = cols.len() > shown_cols.len();
+    let rows = lines
+        .iter()
+        .copied()
+        .into_group_map_by(|line| cross.rows.value(line, printer));
+    let mut title = format!(
+        "{} {} × {}",
+        rows.len().to_formatted_string(&Locale::en),
+        cross.rows.name(),
+        cross.cols.name(),
+    );
+    if rows.len() > row_limit {
+        title.push_str(&format!(". {} most frequent:", row_limit));
+    }
+    // the names of the column placeholders must outlive the expander
+    let col_names: Vec<String> = (0..shown_cols.len()).map(|idx| format!("c{}", idx)).collect();
+    let header_names: Vec<String> = (0..shown_cols.len()).map(|idx| format!("h{}", idx)).collect();
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("title", title)
+        .set("row-key", cross.rows.name());
+    for (col, header_name) in shown_cols.iter().zip(&header_names) {
+        expander.set(header_name, col);
+    }
+    rows.into_iter()
+        .map(|(value, lines)| {
+            let sum = key.sum(&lines);
+            (value, lines, sum)
+        })
+        .sorted_unstable_by_key(|(value, _, sum)| (Reverse(*sum), value.clone()))
+        .take(row_limit)
+        .enumerate()
+        .for_each(|(idx, (value, lines, sum))| {
+            let mut cells = lines
+                .into_iter()
+                .into_group_map_by(|line| cross.cols.value(line, printer));
+            let cell = |lines: &[&LogLine]| {
+                let cell_sum = key.sum(lines);
+                if cell_sum == 0 {
+                    String::new()
+                } else {
+                    format!(
+                        "{} ({:.0}%)",
+                        cell_sum.to_formatted_string(&Locale::en),
+                        100.0 * cell_sum as f64 / sum as f64,
+                    )
+                }
+            };
+            let sub = expander.sub("rows");
+            sub.set("idx", idx + 1)
+                .set("row-value", &value)
+                .set_md("total", printer.md_key_sum(sum));
+            for (col, col_name) in shown_cols.iter().zip(&col_names) {
+                if let Some(lines) = cells.remove(col) {
+                    sub.set(col_name, cell(&lines));
+                }
+            }
+            if with_other {
+                let others: Vec<&LogLine> = cells.into_values().flatten().collect();
+                sub.set("other", cell(&others));
+            }
+        });
+    printer.print(expander, &template(shown_cols.len(), with_other));
+}
@@ -0,0 +1,31 @@
+use {
+    super::*,
+    crate::*,
+};
+
+/// Print the table of a grouping defined with `--group-by`
+pub fn print_custom_grouping(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    grouping: &CustomGrouping,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let section = Section {
+        groups_name: grouping.name,
+        group_key: grouping.name,
+        view: View::Limited(limit),
+        changes: true,
+    };
+    printer.print_groups(
+        &section,
+        log_lines,
+        |_| true,
+        |line| grouping.key(line, printer),
+        trend_computer,
+    );
+}
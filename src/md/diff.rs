@@ -0,0 +1,252 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    num_format::{Locale, ToFormattedString},
+    std::cmp::Reverse,
+    termimad::minimad::OwningTemplateExpander,
+};
+
+static MD_SUMMARY: &str = r#"
+## comparison of log sets
+* **A**: ${name-a}
+* **B**: ${name-b}
+|:-|:-:|:-:|:-:
+|**metric**|**A**|**B**|**Δ**
+|:-|-:|-:|-:
+${metrics
+|${metric}|${a}|${b}|${delta}
+}
+|-:
+"#;
+
+static MD_STATUS: &str = r#"
+## status classes: ${significance}
+|:-:|:-:|:-:|:-:|:-:|:-:
+|**status**|**A hits**|**A %**|**B hits**|**B %**|**Δ points**
+|:-:|-:|-:|-:|-:|-:
+${classes
+|${class}|${hits-a}|${percent-a}|${hits-b}|${percent-b}|${delta}
+}
+|-:
+"#;
+
+static MD_PATHS: &str = r#"
+## ${title}
+|:-:|:-|:-:|:-:|:-:|:-:|:-:|:-:|:-:
+|**#**|**path**|**A %**|**B %**|**A errors**|**B errors**|**A bytes/hit**|**B bytes/hit**|**sig.**
+|-:|:-|-:|-:|-:|-:|-:|-:|:-:
+${paths
+|${idx}|${path}|${percent-a}|${percent-b}|${errors-a}|${errors-b}|${bph-a}|${bph-b}|${significance}
+}
+|-:
+"#;
+
+static MD_ONE_SIDE: &str = r#"
+## paths failing only in ${side}
+|:-|:-:|:-:|:-:
+|**path**|**errors in ${side}**|**hits in ${side}**|**hits in ${other}**
+|:-|-:|-:|-:
+${paths
+|${path}|${errors}|${hits}|${other-hits}
+}
+|-:
+"#;
+
+/// Print the comparison of two log sets: totals, status classes,
+/// top paths, and the paths failing in only one set
+pub fn print_diff(
+    diff: &LogDiff,
+    names: [&str; 2],
+    printer: &Printer,
+) {
+    let limit = match printer.detail_level {
+        0 => 10,
+        l => l * 20,
+    };
+    print_summary(diff, names, printer);
+    print_status_classes(diff, printer);
+    print_top_paths(diff, limit, printer);
+    print_one_side_failures(diff, 0, limit, printer);
+    print_one_side_failures(diff, 1, limit, printer);
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * part as f64 / total as f64
+    }
+}
+
+fn print_summary(
+    diff: &LogDiff,
+    names: [&str; 2],
+    printer: &Printer,
+) {
+    let [a, b] = diff.totals;
+    let server_errors = diff.status_classes[4];
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set("name-a", names[0])
+        .set("name-b", names[1]);
+    expander.sub("metrics")
+        .set("metric", "hits")
+        .set_md("a", printer.md_hits(a.hits as usize))
+        .set_md("b", printer.md_hits(b.hits as usize))
+        .set("delta", percent_change(a.hits as f64, b.hits as f64));
+    if printer.visitors {
+        let visitors = diff.bases.map(|base| count_visitors(&base.lines));
+        expander.sub("metrics")
+            .set("metric", "visitors")
+            .set_md("a", printer.md_visitors(visitors[0]))
+            .set_md("b", printer.md_visitors(visitors[1]))
+            .set("delta", percent_change(visitors[0] as f64, visitors[1] as f64));
+    }
+    expander.sub("metrics")
+        .set("metric", "bytes")
+        .set_md("a", printer.md_bytes(a.bytes))
+        .set_md("b", printer.md_bytes(b.bytes))
+        .set("delta", percent_change(a.bytes as f64, b.bytes as f64));
+    expander.sub("metrics")
+        .set("metric", "bytes per hit")
+        .set("a", file_size::fit_4(a.bytes_per_hit()))
+        .set("b", file_size::fit_4(b.bytes_per_hit()))
+        .set("delta", percent_change(a.bytes_per_hit() as f64, b.bytes_per_hit() as f64));
+    let significance = proportion_significance(a.errors, a.hits, b.errors, b.hits);
+    expander.sub("metrics")
+        .set("metric", "error rate (4xx, 5xx)")
+        .set("a", format!("{:.2}%", 100.0 * a.error_rate()))
+        .set("b", format!("{:.2}%", 100.0 * b.error_rate()))
+        .set("delta", format!(
+            "{:+.2} pts {}",
+            100.0 * (a.error_rate() - b.error_rate()),
+            significance.stars(),
+        ));
+    let significance = proportion_significance(server_errors[0], a.hits, server_errors[1], b.hits);
+    expander.sub("metrics")
+        .set("metric", "server error rate (5xx)")
+        .set("a", format!("{:.2}%", percent(server_errors[0], a.hits)))
+        .set("b", format!("{:.2}%", percent(server_errors[1], b.hits)))
+        .set("delta", format!(
+            "{:+.2} pts {}",
+            percent(server_errors[0], a.hits) - percent(server_errors[1], b.hits),
+            significance.stars(),
+        ));
+    printer.print(expander, MD_SUMMARY);
+}
+
+fn print_status_classes(
+    diff: &LogDiff,
+    printer: &Printer,
+) {
+    let totals = diff.totals.map(|t| t.hits);
+    let mut expander = OwningTemplateExpander::new();
+    match diff.status_significance() {
+        Some((chi2, significance)) => {
+            expander.set(
+                "significance",
+                format!("χ² = {:.1}, {}", chi2, significance.description()),
+            );
+        }
+        None => {
+            expander.set("significance", "too few hits for a chi-square test");
+        }
+    }
+    for (idx, hits) in diff.status_classes.iter().enumerate() {
+        // informational responses are rare, they're shown only when present
+        if idx == 0 && hits[0] + hits[1] == 0 {
+            continue;
+        }
+        let (pa, pb) = (percent(hits[0], totals[0]), percent(hits[1], totals[1]));
+        expander.sub("classes")
+            .set("class", format!("{}xx", idx + 1))
+            .set_md("hits-a", printer.md_hits(hits[0] as usize))
+            .set("percent-a", format!("{:.1}%", pa))
+            .set_md("hits-b", printer.md_hits(hits[1] as usize))
+            .set("percent-b", format!("{:.1}%", pb))
+            .set("delta", format!("{:+.1}", pa - pb));
+    }
+    printer.print(expander, MD_STATUS);
+}
+
+fn print_top_paths(
+    diff: &LogDiff,
+    limit: usize,
+    printer: &Printer,
+) {
+    let totals = diff.totals.map(|t| t.hits);
+    let share = |counts: &[DiffCounts; 2]| {
+        percent(counts[0].hits, totals[0]) + percent(counts[1].hits, totals[1])
+    };
+    let mut title = format!(
+        "{} paths",
+        diff.paths.len().to_formatted_string(&Locale::en),
+    );
+    if diff.paths.len() > limit {
+        title.push_str(&format!(". {} most frequent:", limit));
+    }
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("title", title);
+    diff.paths
+        .iter()
+        .sorted_unstable_by(|(_, a), (_, b)| share(b).partial_cmp(&share(a)).unwrap())
+        .take(limit)
+        .enumerate()
+        .for_each(|(idx, (path, counts))| {
+            let errors = |side: usize| {
+                let c = counts[side];
+                if c.hits == 0 {
+                    String::new()
+                } else {
+                    format!("{} ({:.0}%)", c.errors, 100.0 * c.error_rate())
+                }
+            };
+            expander.sub("paths")
+                .set("idx", idx + 1)
+                .set("path", path)
+                .set("percent-a", format!("{:.1}%", percent(counts[0].hits, totals[0])))
+                .set("percent-b", format!("{:.1}%", percent(counts[1].hits, totals[1])))
+                .set("errors-a", errors(0))
+                .set("errors-b", errors(1))
+                .set("bph-a", file_size::fit_4(counts[0].bytes_per_hit()))
+                .set("bph-b", file_size::fit_4(counts[1].bytes_per_hit()))
+                .set("significance", LogDiff::error_significance(counts).stars());
+        });
+    printer.print(expander, MD_PATHS);
+}
+
+/// Print the paths with errors in one log set and served
+/// without error in the other one
+fn print_one_side_failures(
+    diff: &LogDiff,
+    side: usize,
+    limit: usize,
+    printer: &Printer,
+) {
+    let other = 1 - side;
+    let paths: Vec<(&&str, &[DiffCounts; 2])> = diff.paths
+        .iter()
+        .filter(|(_, counts)| counts[side].errors > 0 && counts[other].errors == 0 && counts[other].hits > 0)
+        .sorted_unstable_by_key(|(path, counts)| (Reverse(counts[side].errors), **path))
+        .take(limit)
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    let names = ["A", "B"];
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set("side", names[side])
+        .set("other", names[other]);
+    for (path, counts) in paths {
+        expander.sub("paths")
+            .set("path", path)
+            .set("errors", counts[side].errors.to_formatted_string(&Locale::en))
+            .set_md("hits", printer.md_hits(counts[side].hits as usize))
+            .set_md("other-hits", printer.md_hits(counts[other].hits as usize));
+    }
+    printer.print(expander, MD_ONE_SIDE);
+}
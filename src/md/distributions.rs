@@ -0,0 +1,68 @@
+use {
+    super::*,
+    crate::*,
+    num_format::{Locale, ToFormattedString},
+    termimad::{
+        minimad::OwningTemplateExpander,
+        ProgressBar,
+    },
+};
+
+static MD: &str = r#"
+## ${title}
+|:-:|:-:|:-:|:-:|:-
+|**${values}**|**${items}**|**%**|**% of ${values}**|**${scale}**
+|:-|-:|-:|-:|:-
+${buckets
+|${range}|${count}|${count-percent}|${sum-percent}|*${bar}*
+}
+|-:
+"#;
+
+/// Return the label of the range of a bucket, as `[min, max)` so that
+/// the bounds of sizes, which are rounded, don't overlap
+fn range_label(distribution: &Distribution, bucket: &DistributionBucket) -> String {
+    let format = |value: u64| {
+        if distribution.bytes {
+            file_size::fit_4(value)
+        } else {
+            value.to_formatted_string(&Locale::en)
+        }
+    };
+    if bucket.min == bucket.max {
+        format(bucket.min)
+    } else {
+        format!("[{}, {})", format(bucket.min), format(bucket.max + 1))
+    }
+}
+
+/// Print the distributions of the hits per IP address, of the sizes
+/// of the responses, and of the hits per path, in log-scaled buckets
+pub fn print_distributions(
+    log_lines: &[LogLine],
+    printer: &Printer,
+) {
+    for distribution in compute_distributions(log_lines, printer.all_paths) {
+        let max_count = match distribution.buckets.iter().map(|b| b.count).max() {
+            Some(max_count) if max_count > 0 => max_count,
+            _ => continue,
+        };
+        let total_count = distribution.total_count() as usize;
+        let total_sum = distribution.total_sum() as usize;
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set("title", distribution.title)
+            .set("values", distribution.values_name)
+            .set("items", distribution.items_name)
+            .set("scale", format!("0               {:>4}", file_size::fit_4(max_count)));
+        for bucket in &distribution.buckets {
+            expander.sub("buckets")
+                .set("range", range_label(&distribution, bucket))
+                .set("count", bucket.count.to_formatted_string(&Locale::en))
+                .set("count-percent", to_percent(bucket.count as usize, total_count))
+                .set("sum-percent", to_percent(bucket.sum as usize, total_sum.max(1)))
+                .set("bar", ProgressBar::new(bucket.count as f32 / max_count as f32, 20));
+        }
+        printer.print(expander, MD);
+    }
+}
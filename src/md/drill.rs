@@ -0,0 +1,31 @@
+use {
+    super::*,
+    crate::*,
+};
+
+/// Print the most frequent values of the parent field, each one
+/// followed by the most frequent values of the children fields
+pub fn print_drill(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    drill: &DrillFields,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 10,
+    };
+    let section = Section {
+        groups_name: drill.parent.plural_name(),
+        group_key: drill.parent.name(),
+        view: View::Limited(limit),
+        changes: false,
+    };
+    printer.print_groups_drill(
+        &section,
+        log_lines,
+        |line| drill.parent.accepts(line, printer),
+        |line| drill.parent.value(line, printer),
+        &drill.children,
+    );
+}
@@ -0,0 +1,111 @@
+use {
+    super::*,
+    crate::*,
+    minimad::{OwningTemplateExpander, TextTemplate},
+    termimad::*,
+};
+
+static MD_LINE: &str = r#"
+## Log line
+|:-|:-
+|**remote address**|${remote-addr}
+|**date time**|${date-time}
+|**method**|${method}
+|**path**|${path}
+|**status**|${status}
+|**bytes sent**|${bytes}
+|**referer**|${referer}
+|**user agent**|${user-agent}
+|**bot**|${bot}
+|**tags**|${tags}
+|-
+"#;
+
+static MD_VERDICTS: &str = r#"
+|:-:|:-|:-|:-:
+|**#**|**filter**|**pattern**|**verdict**
+|-:|:-|:-|:-:
+${filterings
+|${idx}|${field}|${pattern}|${verdict}
+}
+|-
+${conclusion}
+"#;
+
+fn print(skin: &MadSkin, expander: OwningTemplateExpander, template: &str) {
+    let template = TextTemplate::from(template);
+    let text = expander.expand(&template);
+    let width = terminal_size().0 as usize;
+    print!("{}", FmtText::from_text(skin, text, Some(width)));
+}
+
+/// Print how every filtering was understood
+pub fn print_filters(filterer: &Filterer, color: bool) {
+    let skin = skin::make_skin(color);
+    if !filterer.has_filters() {
+        skin.print_text("No filter");
+        return;
+    }
+    let mut md = String::from("## Filters\n");
+    for (idx, filtering) in filterer.filterings.iter().enumerate() {
+        md.push_str(&format!(
+            "**{}.** {} filter on pattern `{}`:\n```\n{}\n```\n",
+            idx + 1,
+            filtering.filter.field_name(),
+            filtering.pattern,
+            filtering.filter.explain().trim_end(),
+        ));
+    }
+    skin.print_text(&md);
+}
+
+/// Print the parsed log line then, for every filter, whether
+/// it accepts or rejects it
+pub fn print_line_explanation(
+    filterer: &Filterer,
+    line: &LogLine,
+    tag_names: &[&str],
+    color: bool,
+) {
+    let skin = skin::make_skin(color);
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set("remote-addr", &line.remote_addr)
+        .set("date-time", line.date_time)
+        .set("method", line.method)
+        .set("path", &line.path)
+        .set("status", line.status)
+        .set("bytes", line.bytes_sent)
+        .set("referer", &line.referer)
+        .set("user-agent", line.user_agent)
+        .set("bot", line.bot)
+        .set("tags", line.tags.join(tag_names, ", "));
+    print(&skin, expander, MD_LINE);
+    if !filterer.has_filters() {
+        skin.print_text("No filter: the line is **accepted**");
+        return;
+    }
+    let mut expander = OwningTemplateExpander::new();
+    let mut first_rejection = None;
+    for (idx, filtering) in filterer.filterings.iter().enumerate() {
+        let accepted = filtering.filter.accepts(line);
+        if !accepted && first_rejection.is_none() {
+            first_rejection = Some(filtering);
+        }
+        expander.sub("filterings")
+            .set("idx", idx + 1)
+            .set("field", filtering.filter.field_name())
+            .set("pattern", &filtering.pattern)
+            .set_md("verdict", if accepted { "accepted" } else { "**rejected**" });
+    }
+    let conclusion = match first_rejection {
+        Some(filtering) => format!(
+            "The line is **rejected**, first by the {} filter on pattern `{}`",
+            filtering.filter.field_name(),
+            filtering.pattern,
+        ),
+        None => "The line is **accepted** by all filters".to_owned(),
+    };
+    expander.set_md("conclusion", conclusion);
+    print(&skin, expander, MD_VERDICTS);
+}
@@ -1,12 +1,27 @@
 mod addr;
+mod anomalies;
+mod bots;
+mod broken;
+mod comparison;
+mod cross;
+mod custom;
+pub mod diff;
+mod distributions;
+mod drill;
+pub mod explain;
 mod paths;
+mod peaks;
+mod path_tree;
 mod referers;
 pub mod summary;
 mod status;
 mod methods;
 mod printer;
 mod section;
+mod security;
+mod sessions;
 mod skin;
+mod tags;
 
 pub use {
     printer::*,
@@ -28,14 +43,37 @@ pub fn print_analysis(
     // note about times: the first markdown template expansion (whatever it is)
     // costs a lot when there's a filtering. I don't know exactly why.
     let lines = &base.lines;
+    // sessions are computed only once, if needed
+    let mut sessions = None;
+    let timeout = printer.session_timeout as i64 * 60;
     for field in &printer.fields.0 {
         match field {
             Field::Dates => {
-                let histogram = DateHistogram::from(base);
-                time!(
-                    "histogram printing",
-                    histogram.print(printer),
-                );
+                if let Some(comparison) = &printer.comparison {
+                    time!(
+                        "print_periods",
+                        comparison::print_periods(base, printer, comparison),
+                    );
+                } else if let Some(bucket) = printer.time_bucket {
+                    if let Some(mut histogram) = BucketHistogram::from(base, bucket) {
+                        if printer.rps {
+                            histogram = time!(histogram.with_rates(base));
+                        }
+                        time!(
+                            "histogram printing",
+                            histogram.print(printer),
+                        );
+                    }
+                } else {
+                    let mut histogram = DateHistogram::from(base);
+                    if printer.rps {
+                        histogram = time!(histogram.with_rates(base));
+                    }
+                    time!(
+                        "histogram printing",
+                        histogram.print(printer),
+                    );
+                }
             }
             Field::Times => {
                 let histogram = TimeHistogram::from(base);
@@ -44,6 +82,21 @@ pub fn print_analysis(
                     histogram.print(printer),
                 );
             }
+            Field::Heatmap => {
+                let heatmap = Heatmap::from(base);
+                time!(
+                    "heatmap printing",
+                    heatmap.print(printer),
+                );
+            }
+            Field::Calendar => {
+                if let Some(calendar) = Calendar::from(base) {
+                    time!(
+                        "calendar printing",
+                        calendar.print(printer),
+                    );
+                }
+            }
             Field::Methods => {
                 time!(
                     "print_methods",
@@ -74,6 +127,115 @@ pub fn print_analysis(
                     paths::print_paths(lines, printer, trend_computer),
                 );
             }
+            Field::Bots => {
+                time!(
+                    "print_bots",
+                    bots::print_bots(lines, printer, trend_computer),
+                );
+            }
+            Field::RefererDomains => {
+                time!(
+                    "print_referer_domains",
+                    referers::print_referer_domains(lines, printer, trend_computer),
+                );
+            }
+            Field::RefererCategories => {
+                time!(
+                    "print_referer_categories",
+                    referers::print_referer_categories(lines, printer, trend_computer),
+                );
+            }
+            Field::Sessions => {
+                let sessions = sessions.get_or_insert_with(|| time!(sessionize(lines, timeout)));
+                time!(
+                    "print_sessions",
+                    sessions::print_sessions(sessions, base, printer),
+                );
+            }
+            Field::EntryPages => {
+                let sessions = sessions.get_or_insert_with(|| time!(sessionize(lines, timeout)));
+                time!(
+                    "print_entry_pages",
+                    sessions::print_entry_pages(sessions, printer),
+                );
+            }
+            Field::ExitPages => {
+                let sessions = sessions.get_or_insert_with(|| time!(sessionize(lines, timeout)));
+                time!(
+                    "print_exit_pages",
+                    sessions::print_exit_pages(sessions, printer),
+                );
+            }
+            Field::Broken => {
+                time!(
+                    "print_broken",
+                    broken::print_broken(lines, printer, trend_computer),
+                );
+            }
+            Field::Security => {
+                time!(
+                    "print_security",
+                    security::print_security(lines, printer),
+                );
+            }
+            Field::Tags => {
+                time!(
+                    "print_tags",
+                    tags::print_tags(lines, printer, trend_computer),
+                );
+            }
+            Field::Peaks => {
+                time!(
+                    "print_peaks",
+                    peaks::print_peaks(lines, printer),
+                );
+            }
+            Field::Distributions => {
+                time!(
+                    "print_distributions",
+                    distributions::print_distributions(lines, printer),
+                );
+            }
+            Field::Anomalies => {
+                time!(
+                    "print_anomalies",
+                    anomalies::print_anomalies(base, printer),
+                );
+            }
+            Field::PathTree => {
+                time!(
+                    "print_path_tree",
+                    path_tree::print_path_tree(lines, printer, trend_computer),
+                );
+            }
         }
     }
+    for grouping in &printer.groupings {
+        time!(
+            "print_custom_grouping",
+            custom::print_custom_grouping(lines, printer, grouping, trend_computer),
+        );
+    }
+    for drill in &printer.drills {
+        time!(
+            "print_drill",
+            drill::print_drill(lines, printer, drill),
+        );
+    }
+    for cross in &printer.crosses {
+        time!(
+            "print_cross",
+            cross::print_cross(lines, printer, *cross),
+        );
+    }
+}
+
+/// Return the relative change from `b` to `a`, eg `+25%`, or
+/// an empty string when `b` is zero
+fn percent_change(a: f64, b: f64) -> String {
+    if b == 0.0 {
+        "".to_owned()
+    } else {
+        format!("{:+.0}%", 100.0 * (a - b) / b)
+    }
 }
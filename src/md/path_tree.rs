@@ -0,0 +1,112 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    termimad::minimad::OwningTemplateExpander,
+    std::cmp::Reverse,
+};
+
+static MD_TREE_NO_TRENDS: &str = r#"
+## ${title}
+|:-|:-:|:-:|:-:
+|**directory**|**hits**|**%**|**bytes**
+|:-|-:|-:|-:
+${dirs
+|${dir}|${hits}|${percent}|${bytes}
+}
+|-:
+"#;
+
+static MD_TREE_TRENDS: &str = r#"
+## ${title}
+|:-|:-:|:-:|:-:|:-:|:-:
+|**directory**|**hits**|**%**|**bytes**|**${histo-name}**|**trend**
+|:-|-:|-:|-:|-:|:-:
+${dirs
+|${dir}|${hits}|${percent}|${bytes}|*${histo-line}*|${trend}
+}
+|-:
+"#;
+
+/// Print the directories as an indented tree, each one with the
+/// sum of the hits of all the paths it contains
+pub fn print_path_tree(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    // max number of children displayed for each directory
+    let limit = match printer.detail_level {
+        0 => 3,
+        1 => 5,
+        l => l * 5,
+    };
+    let root = PathTreeNode::build(
+        log_lines
+            .iter()
+            .filter(|line| printer.all_paths || !line.is_resource()),
+        printer.tree_depth,
+    );
+    if root.hits() == 0 {
+        return;
+    }
+    let title = if printer.all_paths {
+        "path tree".to_owned()
+    } else {
+        "path tree (excluding resources like images, css, etc.)".to_owned()
+    };
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("histo-name", printer.trend_bucket.plural_name())
+        .set("title", title);
+    add_node(&mut expander, &root, root.hits(), limit, printer, trend_computer);
+    let template = if trend_computer.is_some() {
+        MD_TREE_TRENDS
+    } else {
+        MD_TREE_NO_TRENDS
+    };
+    printer.print(expander, template);
+}
+
+fn add_node(
+    expander: &mut OwningTemplateExpander,
+    node: &PathTreeNode,
+    total: usize,
+    limit: usize,
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    // non breaking spaces, as normal ones would be trimmed in cells
+    let indent = "\u{a0}\u{a0}".repeat(node.depth);
+    let sub = expander.sub("dirs");
+    sub.set("dir", format!("{}{}", indent, node.dir))
+        .set_md("hits", printer.md_hits(node.hits()))
+        .set("percent", to_percent(node.hits(), total))
+        .set_md("bytes", printer.md_bytes(node.bytes));
+    if let Some(trend_computer) = trend_computer {
+        let trend = trend_computer.compute_trend(&node.lines);
+        sub.set("histo-line", trend.histo_line());
+        if node.hits() > 9 {
+            sub.set_md("trend", trend.markdown());
+        }
+    }
+    let children = node
+        .children
+        .iter()
+        .sorted_unstable_by_key(|child| Reverse(child.key_sum(printer.key)));
+    let mut omitted = 0;
+    for (idx, child) in children.enumerate() {
+        if idx < limit {
+            add_node(expander, child, total, limit, printer, trend_computer);
+        } else {
+            omitted += 1;
+        }
+    }
+    if omitted > 0 {
+        let indent = "\u{a0}\u{a0}".repeat(node.depth + 1);
+        expander
+            .sub("dirs")
+            .set("dir", format!("{}*{} more*", indent, omitted));
+    }
+}
@@ -0,0 +1,73 @@
+use {
+    super::*,
+    crate::*,
+    termimad::minimad::OwningTemplateExpander,
+};
+
+static MD_RATES: &str = r#"
+## hits per second
+|:-:|:-:|:-:|:-:
+|**p50**|**p95**|**p99**|**max**
+|-:|-:|-:|-:
+|${p50}|${p95}|${p99}|${max}
+|-
+"#;
+
+static MD_PEAKS: &str = r#"
+## ${title}
+|:-:|:-:|:-:|:-|:-|:-:
+|**start**|**hits**|**hits/s**|**top path**|**top IP**|**in flight**
+|:-|-:|-:|:-|:-|-:
+${peaks
+|${start}|${hits}|${rate}|${path}|${ip}|${concurrency}
+}
+|-
+"#;
+
+/// Print the percentiles of the hits per second over the period,
+/// then the busiest seconds and minutes, with the number of requests
+/// in flight estimated from their `$request_time`, when it's logged
+pub fn print_peaks(
+    log_lines: &[LogLine],
+    printer: &Printer,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let rates = rate_percentiles(log_lines);
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set("p50", rates.p50)
+        .set("p95", rates.p95)
+        .set("p99", rates.p99)
+        .set("max", rates.max);
+    printer.print(expander, MD_RATES);
+    for (duration, title) in [(1, "busiest seconds"), (60, "busiest minutes")] {
+        let peaks = find_peaks(log_lines, duration, limit);
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set_default("")
+            .set("title", title);
+        for peak in &peaks {
+            let sub = expander.sub("peaks");
+            let time = peak.start.time;
+            let start = if duration < 60 {
+                format!("{} {}", peak.start.date, time)
+            } else {
+                format!("{} {:0>2}:{:0>2}", peak.start.date, time.hour, time.minute)
+            };
+            sub
+                .set("start", start)
+                .set_md("hits", printer.md_hits(peak.hits))
+                .set("rate", format!("{:.1}", peak.rate()))
+                .set("path", format!("{} ({}%)", peak.top_path.0, 100 * peak.top_path.1 / peak.hits))
+                .set("ip", format!("{} ({}%)", peak.top_ip.0, 100 * peak.top_ip.1 / peak.hits));
+            if let Some(concurrency) = peak.concurrency {
+                sub.set("concurrency", format!("{:.1}", concurrency));
+            }
+        }
+        printer.print(expander, MD_PEAKS);
+    }
+}
@@ -6,7 +6,9 @@ use {
     minimad::{OwningTemplateExpander, TextTemplate},
     num_format::{Locale, ToFormattedString, WriteFormatted},
     std::{
+        borrow::Cow,
         cmp::Reverse,
+        collections::HashMap,
         fmt::Display,
         hash::Hash,
     },
@@ -15,33 +17,55 @@ use {
 
 static MD_GROUPS_NO_TRENDS: &str = r#"
 ## ${groups-count} ${groups-name}. ${limited}
-|:-:|:-|:-:|:-:|:-:
-|**#**|**${group-key}**|**hits**|**%**|**bytes**
-|-:|:-|-:|-:|-:
+|:-:|:-|:-:|:-:|:-:|:-:
+|**#**|**${group-key}**|**hits**|**%**|**visitors**|**bytes**
+|-:|:-|-:|-:|-:|-:
 ${groups
-|${idx}|${group-value}|${hits}|${percent}|${bytes}
+|${idx}|${group-value}|${hits}|${percent}|${visitors}|${bytes}
 }
 |-:
 "#;
 
 static MD_GROUPS_TRENDS_NO_ROW_IDX: &str = r#"
 ## ${title}
-|:-|:-:|:-:|:-:|:-:|:-:
-|**${group-key}**|**hits**|**%**|**bytes**|**days**|**trend**
-|:-:|-:|-:|-:|-:|:-:|
+|:-|:-:|:-:|:-:|:-:|:-:|:-:
+|**${group-key}**|**hits**|**%**|**visitors**|**bytes**|**${histo-name}**|**trend**
+|:-:|-:|-:|-:|-:|-:|:-:|
 ${groups
-|${group-value}|${hits}|${percent}|${bytes}|*${histo-line}*|${trend}
+|${group-value}|${hits}|${percent}|${visitors}|${bytes}|*${histo-line}*|${trend}
 }
 |-:
 "#;
 
 static MD_GROUPS_TRENDS: &str = r#"
 ## ${title}
-|:-:|:-|:-:|:-:|:-:|:-:
-|**#**|**${group-key}**|**hits**|**bytes**|**days**|**trend**
-|-:|:-|-:|-:|-:|:-:|
+|:-:|:-|:-:|:-:|:-:|:-:|:-:
+|**#**|**${group-key}**|**hits**|**visitors**|**bytes**|**${histo-name}**|**trend**
+|-:|:-|-:|-:|-:|-:|:-:|
+${groups
+|${idx}|${group-value}|${hits}|${visitors}|${bytes}|*${histo-line}*|${trend}
+}
+|-:
+"#;
+
+static MD_GROUPS_DRILL: &str = r#"
+## ${title}
+|:-:|:-|:-:|:-:|:-:
+|**#**|**${group-key}**|**${key}**|**%**|**bytes**
+|-:|:-|-:|-:|-:
+${rows
+|${idx}|${group-value}|${key-sum}|${percent}|${bytes}
+}
+|-:
+"#;
+
+static MD_GROUPS_COMPARISON: &str = r#"
+## ${title}
+|:-:|:-|:-:|:-:|:-:|:-:|:-:|:-:|:-:|:-:
+|**#**|**${group-key}**|**A hits**|**A %**|**A bytes**|**B hits**|**B %**|**B bytes**|**Δ ${key}**|**Δ %**
+|-:|:-|-:|-:|-:|-:|-:|-:|-:|-:
 ${groups
-|${idx}|${group-value}|${hits}|${bytes}|*${histo-line}*|${trend}
+|${idx}|${group-value}|${hits-a}|${percent-a}|${bytes-a}|${hits-b}|${percent-b}|${bytes-b}|${delta}|${change}
 }
 |-:
 "#;
@@ -52,39 +76,105 @@ pub struct Printer {
     pub terminal_width: usize,
     pub detail_level: usize,
     pub key: Key,
-    pub date_filter: Option<DateTimeFilter>,
+    pub date_filters: Vec<DateTimeFilter>,
     pub changes: bool,
     pub all_paths: bool,
+    pub tree_depth: usize,
+    pub referer_classifier: RefererClassifier,
+    pub registrable_domains: bool,
+    pub session_timeout: u32,
+    pub anomaly_threshold: f64,
+    pub trend_bucket: TrendBucket,
+    /// when set, the dates table is a histogram in those buckets
+    pub time_bucket: Option<TimeBucket>,
+    /// whether percentiles of hits per second are added to the dates table
+    pub rps: bool,
+    /// when set, tables compare two periods
+    pub comparison: Option<Comparison>,
+    pub crosses: Vec<CrossFields>,
+    pub drills: Vec<DrillFields>,
+    pub groupings: Vec<CustomGrouping>,
+    /// the names of the tags, indexed by their id
+    pub tag_names: Vec<&'static str>,
+    /// whether distinct visitors are counted, and shown in the tables
+    pub visitors: bool,
 }
 
 impl Printer {
-    pub fn new(args: &args::Args, log_base: &LogBase) -> Self {
+    pub fn new(
+        args: &args::Args,
+        log_base: &LogBase,
+        comparison: Option<Comparison>,
+    ) -> Self {
         let detail_level = args.length;
         let fields = args.fields.clone();
         let terminal_width = terminal_size().0 as usize;
         let color = args.color();
         let skin = skin::make_skin(color);
         let key = args.key;
-        let date_filter = log_base.filterer.date_filter().copied();
+        let date_filters = log_base.filterer.date_filters().copied().collect();
         let changes = args.changes;
         let all_paths = args.all;
+        let tree_depth = args.tree_depth;
+        let own_domains = args.config.own_domains
+            .iter()
+            .chain(args.own_domain.iter())
+            .cloned()
+            .collect();
+        let referer_classifier = RefererClassifier::new(own_domains);
+        let registrable_domains = args.registrable_domains;
+        let session_timeout = args.session_timeout;
+        let anomaly_threshold = args.anomaly_threshold;
+        let trend_bucket = TrendWindows::new(args).bucket;
+        let time_bucket = args.bucket;
+        let rps = args.rps;
+        let crosses = args.cross.clone();
+        let drills = args.drill.clone();
+        let groupings = args.group_by.clone();
+        let tag_names = log_base.tag_names.clone();
+        let visitors = log_base.visitors;
         Self {
             skin,
             fields,
             terminal_width,
             detail_level,
             key,
-            date_filter,
+            date_filters,
             changes,
             all_paths,
+            tree_depth,
+            referer_classifier,
+            registrable_domains,
+            session_timeout,
+            anomaly_threshold,
+            trend_bucket,
+            time_bucket,
+            rps,
+            comparison,
+            crosses,
+            drills,
+            groupings,
+            tag_names,
+            visitors,
         }
     }
+    /// Tell whether the day isn't excluded by a date filter
+    pub fn shows_date(&self, date: Date) -> bool {
+        self.date_filters.iter().all(|f| f.overlaps(date))
+    }
+    /// Expand and print a template, whose visitors column, if any,
+    /// is removed when visitors aren't counted
     pub fn print(
         &self,
         expander: OwningTemplateExpander,
         template: &str,
     ) {
-        let template = TextTemplate::from(template);
+        let template = if self.visitors {
+            Cow::Borrowed(template)
+        } else {
+            Cow::Owned(remove_column(template, "**visitors**"))
+        };
+        let template = TextTemplate::from(&*template);
         let text = expander.expand(&template);
         let fmt_text = FmtText::from_text(&self.skin, text, Some(self.terminal_width));
         print!("{}", fmt_text);
@@ -97,14 +187,21 @@ impl Printer {
                 s.push('*');
                 s
             }
-            Key::Bytes => hits.to_formatted_string(&Locale::en),
+            _ => hits.to_formatted_string(&Locale::en),
+        }
+    }
+    pub fn md_visitors(&self, visitors: usize) -> String {
+        let s = visitors.to_formatted_string(&Locale::en);
+        match self.key {
+            Key::Visitors => format!("*{}*", s),
+            _ => s,
         }
     }
     pub fn md_bytes(&self, bytes: u64) -> String {
         let s = file_size::fit_4(bytes);
         match self.key {
-            Key::Hits => s,
             Key::Bytes => format!("*{}*", s),
+            _ => s,
         }
     }
 
@@ -120,7 +217,15 @@ impl Printer {
         F: Fn(&&LogLine) -> bool,
         G: for<'a> Fn(&'a &'b LogLine) -> T,
     {
-        if let Some(trend_computer) = trend_computer {
+        if let Some(comparison) = &self.comparison {
+            self.print_groups_comparison(
+                section,
+                log_lines,
+                filter,
+                grouper,
+                comparison,
+            );
+        } else if let Some(trend_computer) = trend_computer {
             self.print_groups_trends(
                 section,
                 log_lines,
@@ -180,6 +285,7 @@ impl Printer {
                 let key_sum = match self.key {
                     Key::Hits  => lines.len() as u64,
                     Key::Bytes => bytes,
+                    Key::Visitors => count_visitors(lines.iter().copied()) as u64,
                 };
                 (value, Group { lines, bytes, key_sum })
             })
@@ -194,10 +300,218 @@ impl Printer {
                     .set_md("hits", self.md_hits(g.lines.len()))
                     .set("percent", to_percent(g.lines.len(), log_lines.len()))
                     .set_md("bytes", self.md_bytes(g.bytes));
+                if self.visitors {
+                    sub.set_md("visitors", self.md_visitors(self.group_visitors(&g.lines, g.key_sum)));
+                }
             });
         self.print(expander, MD_GROUPS_NO_TRENDS);
     }
 
+    /// Print the groups with their hits and bytes in both periods
+    /// of the comparison, and the change of the key
+    pub fn print_groups_comparison<'b, T, F, G>(
+        &self,
+        section: &Section,
+        log_lines: &'b [LogLine],
+        filter: F,
+        grouper: G,
+        comparison: &Comparison,
+    ) where
+        T: Display + Hash + Eq + 'b,
+        F: Fn(&&LogLine) -> bool,
+        G: for<'a> Fn(&'a &'b LogLine) -> T,
+    {
+        struct Side {
+            hits: usize,
+            bytes: u64,
+            key_sum: u64,
+        }
+        let mut totals = [0; 2];
+        let mut groups: HashMap<T, [Vec<&LogLine>; 2]> = HashMap::new();
+        for line in log_lines {
+            let side = match comparison.side(line.date()) {
+                Some(side) => side,
+                None => continue,
+            };
+            totals[side] += 1;
+            if filter(&line) {
+                groups.entry(grouper(&line)).or_default()[side].push(line);
+            }
+        }
+        let groups_count = groups.len();
+        let rows: Vec<(T, [Side; 2], i64)> = groups
+            .into_iter()
+            .map(|(value, sides)| {
+                let sides = sides.map(|lines| {
+                    let bytes = lines.iter().map(|ll| ll.bytes_sent).sum();
+                    let key_sum = match self.key {
+                        Key::Hits => lines.len() as u64,
+                        Key::Bytes => bytes,
+                        Key::Visitors => count_visitors(lines.iter().copied()) as u64,
+                    };
+                    Side { hits: lines.len(), bytes, key_sum }
+                });
+                let delta = sides[0].key_sum as i64 - sides[1].key_sum as i64;
+                (value, sides, delta)
+            })
+            .collect();
+        let limit = section.view.limit();
+        let mut title = format!(
+            "{} {}",
+            groups_count.to_formatted_string(&Locale::en),
+            section.groups_name,
+        );
+        if groups_count > limit {
+            let order = match comparison.sort {
+                CompareSort::Total => "most frequent",
+                CompareSort::Gain => "biggest gains",
+                CompareSort::Loss => "biggest losses",
+                CompareSort::Change => "biggest changes",
+            };
+            title.push_str(&format!(". {} {}:", limit, order));
+        }
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set_default("")
+            .set("group-key", section.group_key)
+            .set("key", self.key.name())
+            .set("title", title);
+        rows.into_iter()
+            .sorted_unstable_by_key(|(_, sides, delta)| match comparison.sort {
+                CompareSort::Total => -((sides[0].key_sum + sides[1].key_sum) as i64),
+                CompareSort::Gain => -delta,
+                CompareSort::Loss => *delta,
+                CompareSort::Change => -delta.abs(),
+            })
+            .take(limit)
+            .enumerate()
+            .for_each(|(idx, (value, sides, delta))| {
+                let sub = expander.sub("groups");
+                sub.set("idx", idx + 1)
+                    .set("group-value", &value)
+                    .set_md("hits-a", self.md_hits(sides[0].hits))
+                    .set("percent-a", to_percent(sides[0].hits, totals[0]))
+                    .set_md("bytes-a", self.md_bytes(sides[0].bytes))
+                    .set_md("hits-b", self.md_hits(sides[1].hits))
+                    .set("percent-b", to_percent(sides[1].hits, totals[1]))
+                    .set_md("bytes-b", self.md_bytes(sides[1].bytes))
+                    .set_md("delta", self.md_delta(delta))
+                    .set("change", match sides[1].key_sum {
+                        0 => "new".to_owned(),
+                        b => format!("{:+.0}%", 100.0 * delta as f64 / b as f64),
+                    });
+            });
+        self.print(expander, MD_GROUPS_COMPARISON);
+    }
+    /// Print the groups, each one followed by the most frequent values
+    /// of the children fields among its lines, with their part of the group
+    pub fn print_groups_drill<'b, T, F, G>(
+        &self,
+        section: &Section,
+        log_lines: &'b [LogLine],
+        filter: F,
+        grouper: G,
+        children: &[LineField],
+    ) where
+        T: Display + Hash + Eq + 'b,
+        F: Fn(&&LogLine) -> bool,
+        G: for<'a> Fn(&'a &'b LogLine) -> T,
+    {
+        let child_limit = match self.detail_level {
+            0 | 1 => 3,
+            l => l + 2,
+        };
+        let lines: Vec<&LogLine> = log_lines.iter().filter(filter).collect();
+        let total = self.key.sum(&lines);
+        let groups: Vec<LineGroup<T>> = lines
+            .into_iter()
+            .into_group_map_by(grouper)
+            .into_iter()
+            .map(|(value, lines)| LineGroup::without_trend(value, lines, self.key))
+            .collect();
+        let mut title = format!(
+            "{} {}, with their {}",
+            groups.len().to_formatted_string(&Locale::en),
+            section.groups_name,
+            children.iter().map(|child| child.plural_name()).join(" and "),
+        );
+        if groups.len() > section.view.limit() {
+            title.push_str(&format!(". {} most frequent:", section.view.limit()));
+        }
+        let mut expander = OwningTemplateExpander::new();
+        expander
+            .set_default("")
+            .set("title", title)
+            .set("group-key", section.group_key)
+            .set("key", self.key.name());
+        groups
+            .iter()
+            // ties are broken on the value, for a stable output
+            .sorted_by_cached_key(|g| (Reverse(g.key_sum), g.value.to_string()))
+            .take(section.view.limit())
+            .enumerate()
+            .for_each(|(idx, g)| {
+                expander.sub("rows")
+                    .set("idx", idx + 1)
+                    .set("group-value", &g.value)
+                    .set_md("key-sum", self.md_key_sum(g.key_sum))
+                    .set("percent", to_percent(g.key_sum as usize, total as usize))
+                    .set_md("bytes", self.md_bytes(g.bytes));
+                for child in children {
+                    g.lines
+                        .iter()
+                        .copied()
+                        .filter(|line| child.accepts(line, self))
+                        .into_group_map_by(|line| child.value(line, self))
+                        .into_iter()
+                        .map(|(child_value, child_lines)| {
+                            LineGroup::without_trend(child_value, child_lines, self.key)
+                        })
+                        .sorted_unstable_by(|a, b| {
+                            (Reverse(a.key_sum), &a.value).cmp(&(Reverse(b.key_sum), &b.value))
+                        })
+                        .take(child_limit)
+                        .for_each(|child_group| {
+                            // non breaking spaces, as normal ones would be trimmed in cells
+                            expander.sub("rows")
+                                .set(
+                                    "group-value",
+                                    format!("\u{a0}\u{a0}↳ {}: {}", child.name(), child_group.value),
+                                )
+                                .set_md("key-sum", self.md_key_sum(child_group.key_sum))
+                                .set("percent", to_percent(child_group.key_sum as usize, g.key_sum as usize))
+                                .set_md("bytes", self.md_bytes(child_group.bytes));
+                        });
+                }
+            });
+        self.print(expander, MD_GROUPS_DRILL);
+    }
+    /// Count the distinct visitors of a group, reusing the sum
+    /// of the key when it's already this count
+    fn group_visitors(&self, lines: &[&LogLine], key_sum: u64) -> usize {
+        match self.key {
+            Key::Visitors => key_sum as usize,
+            _ => count_visitors(lines.iter().copied()),
+        }
+    }
+    /// Format a sum of the key, hits, bytes or visitors
+    pub fn md_key_sum(&self, key_sum: u64) -> String {
+        match self.key {
+            Key::Hits => self.md_hits(key_sum as usize),
+            Key::Bytes => self.md_bytes(key_sum),
+            Key::Visitors => self.md_visitors(key_sum as usize),
+        }
+    }
+    /// Format a signed difference of the key
+    pub fn md_delta(&self, delta: i64) -> String {
+        let sign = if delta < 0 { '-' } else { '+' };
+        let abs = delta.unsigned_abs();
+        match self.key {
+            Key::Bytes => format!("{}{}", sign, file_size::fit_4(abs)),
+            _ => format!("{}{}", sign, abs.to_formatted_string(&Locale::en)),
+        }
+    }
+
     pub fn print_groups_trends<'b, T, F, G>(
         &self,
         section: &Section,
@@ -288,6 +602,7 @@ impl Printer {
         expander
             .set_default("")
             .set("group-key", section.group_key)
+            .set("histo-name", self.trend_bucket.plural_name())
             .set("title", title);
         groups.enumerate().for_each(|(idx, g)| {
             rows_count += 1;
@@ -296,15 +611,21 @@ impl Printer {
                 .set("group-value", &g.value)
                 .set_md("hits", self.md_hits(g.hits()))
                 .set_md("bytes", self.md_bytes(g.bytes))
-                .set("histo-line", g.histo_line())
-                .set("ref_count", g.trend.ref_count)
-                .set("tail_count", g.trend.tail_count);
+                .set("histo-line", g.histo_line());
+            if self.visitors {
+                sub.set_md("visitors", self.md_visitors(self.group_visitors(&g.lines, g.key_sum)));
+            }
+            if let Some(trend) = &g.trend {
+                sub
+                    .set("ref_count", trend.ref_count)
+                    .set("tail_count", trend.tail_count);
+                if g.hits() > 9 {
+                    sub.set_md("trend", trend.markdown());
+                }
+            }
             if matches!(section.view, View::Full) {
                 sub.set("percent", to_percent(g.lines.len(), total_count));
             }
-            if g.hits() > 9 {
-                sub.set_md("trend", g.trend.markdown());
-            }
         });
         if rows_count == 0 {
             println!("{} : none", title);
@@ -318,8 +639,52 @@ impl Printer {
     }
 }
 
-fn to_percent(count: usize, total: usize) -> String {
+/// Remove, from a table template, the column with the given header,
+/// in all the rows having this column
+fn remove_column(template: &str, header: &str) -> String {
+    let idx = template
+        .lines()
+        .find_map(|line| line.split('|').position(|cell| cell == header));
+    let idx = match idx {
+        Some(idx) => idx,
+        None => return template.to_owned(),
+    };
+    let mut s = String::with_capacity(template.len());
+    for line in template.lines() {
+        if line.starts_with('|') && line.split('|').count() > idx {
+            let cells: Vec<&str> = line
+                .split('|')
+                .enumerate()
+                .filter(|&(i, _)| i != idx)
+                .map(|(_, cell)| cell)
+                .collect();
+            s.push_str(&cells.join("|"));
+        } else {
+            s.push_str(line);
+        }
+        s.push('\n');
+    }
+    s
+}
+
+pub fn to_percent(count: usize, total: usize) -> String {
     let percent = 100f32 * (count as f32) / (total as f32);
     format!("{:.1}%", percent)
 }
 
+
+#[cfg(test)]
+mod printer_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_remove_column() {
+        let template = "\n## ${title}\n|:-:|:-:|:-:\n|**hits**|**visitors**|**bytes**\n${rows\n|${hits}|${visitors}|${bytes}\n}\n|-:\n";
+        assert_eq!(
+            remove_column(template, "**visitors**"),
+            "\n## ${title}\n|:-:|:-:\n|**hits**|**bytes**\n${rows\n|${hits}|${bytes}\n}\n|-:\n",
+        );
+        assert_eq!(remove_column(MD_GROUPS_DRILL, "**visitors**"), MD_GROUPS_DRILL);
+    }
+}
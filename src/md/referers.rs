@@ -27,3 +27,60 @@ pub fn print_referers(
         trend_computer,
     );
 }
+
+pub fn print_referer_domains(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let section = Section {
+        groups_name: "referrer domains",
+        group_key: "domain",
+        view: View::Limited(limit),
+        changes: true,
+    };
+    let classifier = &printer.referer_classifier;
+    printer.print_groups(
+        &section,
+        log_lines,
+        |line| {
+            referer_host(&line.referer)
+                .map_or(false, |host| classifier.classify_host(host) != RefererCategory::Internal)
+        },
+        |line| {
+            let host = referer_host(&line.referer).unwrap_or("");
+            if printer.registrable_domains {
+                registrable_domain(host)
+            } else {
+                host
+            }
+        },
+        trend_computer,
+    );
+}
+
+pub fn print_referer_categories(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let section = Section {
+        groups_name: "referrer sources",
+        group_key: "source",
+        view: View::Full,
+        changes: false,
+    };
+    let classifier = &printer.referer_classifier;
+    printer.print_groups(
+        &section,
+        log_lines,
+        |_| true,
+        |line| classifier.classify(&line.referer),
+        trend_computer,
+    );
+}
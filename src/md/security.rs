@@ -0,0 +1,114 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    num_format::{Locale, ToFormattedString},
+    std::cmp::Reverse,
+    termimad::minimad::OwningTemplateExpander,
+};
+
+static MD_SCANNERS: &str = r#"
+## ${groups-count} scanning IP addresses. ${limited}
+|:-:|:-|:-:|:-:|:-|:-:|:-:
+|**#**|**IP address**|**probes**|**hits**|**rules**|**first seen**|**last seen**
+|-:|:-|-:|-:|:-|:-:|:-:
+${groups
+|${idx}|${addr}|${probes}|${hits}|${rules}|${first}|${last}
+}
+|-:
+"#;
+
+static MD_RULES: &str = r#"
+## scan rules
+|:-|:-:|:-:|:-:
+|**rule**|**probes**|**%**|**IP addresses**
+|:-|-:|-:|-:
+${rules
+|${rule}|${probes}|${percent}|${addrs}
+}
+|-:
+"#;
+
+/// Print the remote addresses which sent probes matching scan rules,
+/// then the rules which were triggered
+pub fn print_security(
+    log_lines: &[LogLine],
+    printer: &Printer,
+) {
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let groups = log_lines
+        .iter()
+        .filter(|line| line.scan.is_scan())
+        .into_group_map_by(|line| &line.remote_addr);
+    if groups.is_empty() {
+        println!("No hit of vulnerability scanner");
+        return;
+    }
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("groups-count", groups.len().to_formatted_string(&Locale::en));
+    if groups.len() > limit {
+        expander.set("limited", format!("{} most active:", limit));
+    }
+    groups
+        .into_iter()
+        .map(|(addr, lines)| {
+            let rules: Vec<&'static str> = lines
+                .iter()
+                .filter_map(|line| match line.scan {
+                    ScanKind::Probe(rule) => Some(rule),
+                    _ => None,
+                })
+                .collect();
+            (addr, lines, rules)
+        })
+        .sorted_unstable_by_key(|(_, lines, rules)| Reverse((rules.len(), lines.len())))
+        .take(limit)
+        .enumerate()
+        .for_each(|(idx, (addr, lines, rules))| {
+            let rules = rules
+                .iter()
+                .counts()
+                .into_iter()
+                .sorted_unstable_by_key(|(rule, count)| (Reverse(*count), **rule))
+                .map(|(rule, _)| rule)
+                .join(", ");
+            let probes = lines.iter().filter(|line| matches!(line.scan, ScanKind::Probe(_))).count();
+            expander.sub("groups")
+                .set("idx", idx + 1)
+                .set("addr", addr)
+                .set("probes", probes.to_formatted_string(&Locale::en))
+                .set_md("hits", printer.md_hits(lines.len()))
+                .set("rules", rules)
+                .set("first", lines[0].date_time)
+                .set("last", lines[lines.len() - 1].date_time);
+        });
+    printer.print(expander, MD_SCANNERS);
+    let mut expander = OwningTemplateExpander::new();
+    let probes: Vec<(&'static str, &LogLine)> = log_lines
+        .iter()
+        .filter_map(|line| match line.scan {
+            ScanKind::Probe(rule) => Some((rule, line)),
+            _ => None,
+        })
+        .collect();
+    probes
+        .iter()
+        .into_group_map_by(|(rule, _)| *rule)
+        .into_iter()
+        .sorted_unstable_by_key(|(_, g)| Reverse(g.len()))
+        .for_each(|(rule, g)| {
+            let addrs = g.iter().map(|(_, line)| &line.remote_addr).unique().count();
+            expander.sub("rules")
+                .set("rule", rule)
+                .set("probes", g.len().to_formatted_string(&Locale::en))
+                .set("percent", to_percent(g.len(), probes.len()))
+                .set("addrs", addrs.to_formatted_string(&Locale::en));
+        });
+    printer.print(expander, MD_RULES);
+}
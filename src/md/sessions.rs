@@ -0,0 +1,173 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    num_format::{Locale, ToFormattedString},
+    std::cmp::Reverse,
+    termimad::minimad::{OwningSubTemplateExpander, OwningTemplateExpander},
+};
+
+static MD_SESSIONS: &str = r#"
+## ${sessions-count} sessions, with an inactivity timeout of ${timeout} minutes
+|:-:|:-:|:-:|:-:|:-:
+|**date**|**sessions**|**pages/session**|**duration**|**bounce rate**
+|:-|-:|-:|-:|-:
+${days
+|${date}|${sessions}|${pages}|${duration}|${bounce-rate}
+}
+|-:|-:|-:|-:|-:
+${total
+|**total**|**${sessions}**|**${pages}**|**${duration}**|**${bounce-rate}**
+}
+|-:
+"#;
+
+static MD_PAGES: &str = r#"
+## ${groups-count} ${groups-name}. ${limited}
+|:-:|:-|:-:|:-:|:-:
+|**#**|**${group-key}**|**sessions**|**%**|**bounce rate**
+|-:|:-|-:|-:|-:
+${groups
+|${idx}|${group-value}|${sessions}|${percent}|${bounce-rate}
+}
+|-:
+"#;
+
+/// Format a duration given in seconds, eg `3m 12s`
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m {:0>2}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {:0>2}m", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
+
+/// Sums of the metrics of several sessions
+#[derive(Default)]
+struct SessionsStats {
+    sessions: usize,
+    pages: usize,
+    duration: i64,
+    bounces: usize,
+}
+
+impl SessionsStats {
+    fn add(&mut self, session: &Session) {
+        self.sessions += 1;
+        self.pages += session.pages();
+        self.duration += session.duration();
+        if session.is_bounce() {
+            self.bounces += 1;
+        }
+    }
+    fn set(&self, sub: &mut OwningSubTemplateExpander) {
+        let sessions = self.sessions.max(1) as f32;
+        sub.set("sessions", self.sessions.to_formatted_string(&Locale::en))
+            .set("pages", format!("{:.1}", self.pages as f32 / sessions))
+            .set("duration", format_duration(self.duration as f32 / sessions))
+            .set("bounce-rate", to_percent(self.bounces, self.sessions.max(1)));
+    }
+}
+
+pub fn print_sessions(
+    sessions: &[Session],
+    base: &LogBase,
+    printer: &Printer,
+) {
+    let mut days: Vec<SessionsStats> = base.dates
+        .iter()
+        .map(|_| SessionsStats::default())
+        .collect();
+    let mut total = SessionsStats::default();
+    for session in sessions {
+        days[session.date_idx()].add(session);
+        total.add(session);
+    }
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("sessions-count", sessions.len().to_formatted_string(&Locale::en))
+        .set("timeout", printer.session_timeout);
+    for (date, stats) in base.dates.iter().zip(days.iter()) {
+        if stats.sessions > 0 && printer.shows_date(*date) {
+            let sub = expander.sub("days");
+            sub.set("date", date);
+            stats.set(sub);
+        }
+    }
+    total.set(expander.sub("total"));
+    printer.print(expander, MD_SESSIONS);
+}
+
+fn print_pages<'b, G>(
+    sessions: &[Session<'b>],
+    printer: &Printer,
+    groups_name: &str,
+    group_key: &str,
+    page: G,
+) where
+    G: Fn(&Session<'b>) -> &'b LogLine,
+{
+    let limit = match printer.detail_level {
+        0 => 5,
+        1 => 10,
+        l => l * 20,
+    };
+    let mut expander = OwningTemplateExpander::new();
+    expander
+        .set_default("")
+        .set("groups-name", groups_name)
+        .set("group-key", group_key);
+    let groups = sessions
+        .iter()
+        .into_group_map_by(|session| &page(session).path);
+    expander.set("groups-count", groups.len().to_formatted_string(&Locale::en));
+    if groups.len() > limit {
+        expander.set("limited", format!("{} most frequent:", limit));
+    }
+    groups
+        .into_iter()
+        .sorted_unstable_by_key(|(_, g)| Reverse(g.len()))
+        .take(limit)
+        .enumerate()
+        .for_each(|(idx, (path, g))| {
+            let bounces = g.iter().filter(|session| session.is_bounce()).count();
+            expander.sub("groups")
+                .set("idx", idx + 1)
+                .set("group-value", path)
+                .set("sessions", g.len().to_formatted_string(&Locale::en))
+                .set("percent", to_percent(g.len(), sessions.len()))
+                .set("bounce-rate", to_percent(bounces, g.len()));
+        });
+    printer.print(expander, MD_PAGES);
+}
+
+pub fn print_entry_pages(
+    sessions: &[Session],
+    printer: &Printer,
+) {
+    print_pages(sessions, printer, "entry pages", "entry page", Session::entry);
+}
+
+pub fn print_exit_pages(
+    sessions: &[Session],
+    printer: &Printer,
+) {
+    print_pages(sessions, printer, "exit pages", "exit page", Session::exit);
+}
+
+#[cfg(test)]
+mod sessions_printing_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42.4), "42s");
+        assert_eq!(format_duration(192.0), "3m 12s");
+        assert_eq!(format_duration(7300.0), "2h 01m");
+    }
+}
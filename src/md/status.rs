@@ -37,11 +37,6 @@ pub fn print_status_codes(
     );
 }
 
-fn to_percent(count: usize, total: usize) -> String {
-    let percent = 100f32 * (count as f32) / (total as f32);
-    format!("{:.1}%", percent)
-}
-
 fn print_status_summary(
     log_lines: &[LogLine],
     printer: &Printer,
@@ -5,23 +5,33 @@ use {
 };
 
 static SUMMARY_MD: &str = r#"
-${hits} hits and ${bytes} from **${start}** to **${end}**
+${hits} hits${visitors} and ${bytes} from **${start}** to **${end}**
 ${filterings
 Filtering by ${field} on pattern `${pattern}` removed **${removed_percent}** of total lines
 }
 ${filtered-stats
- **==>** hits: ${hits}, bytes sent: ${bytes}
+ **==>** hits: ${hits}${visitors}, bytes sent: ${bytes}
+}
+${bots
+Humans: ${human_hits} hits (**${human_percent}**), bots: ${bot_hits} hits (**${bot_percent}**)
 }
 "#;
 
 pub fn print_summary(base: &LogBase, printer: &Printer) {
     let mut expander = OwningTemplateExpander::new();
+    expander.set_default("");
     let total_bytes = base.unfiltered_histogram.total_bytes_sent();
     expander
         .set_md("hits", printer.md_hits(base.unfiltered_count as usize))
         .set_md("bytes", printer.md_bytes(total_bytes))
         .set("start", base.start_time())
         .set("end", base.end_time());
+    if printer.visitors {
+        expander.set_md(
+            "visitors",
+            format!(", {} visitors", printer.md_visitors(base.unfiltered_visitors as usize)),
+        );
+    }
     if base.filterer.has_filters() {
         let total_hits = base.unfiltered_count as f32;
         for filtering in &base.filterer.filterings {
@@ -33,9 +43,25 @@ pub fn print_summary(base: &LogBase, printer: &Printer) {
                 .set("removed_percent", percent);
         }
         let filtered_bytes = base.filtered_histogram.total_bytes_sent();
-        expander.sub("filtered-stats")
+        let sub = expander.sub("filtered-stats");
+        sub
             .set_md("hits", printer.md_hits(base.filtered_count as usize))
             .set_md("bytes", printer.md_bytes(filtered_bytes));
+        if printer.visitors {
+            sub.set_md(
+                "visitors",
+                format!(", visitors: {}", printer.md_visitors(count_visitors(&base.lines))),
+            );
+        }
+    }
+    if printer.fields.contains(Field::Bots) && !base.lines.is_empty() {
+        let bot_hits = base.lines.iter().filter(|line| line.bot.is_bot()).count();
+        let human_hits = base.lines.len() - bot_hits;
+        expander.sub("bots")
+            .set_md("human_hits", printer.md_hits(human_hits))
+            .set("human_percent", to_percent(human_hits, base.lines.len()))
+            .set_md("bot_hits", printer.md_hits(bot_hits))
+            .set("bot_percent", to_percent(bot_hits, base.lines.len()));
     }
     printer.print(expander, SUMMARY_MD);
 }
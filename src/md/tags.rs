@@ -0,0 +1,85 @@
+use {
+    super::*,
+    crate::*,
+    itertools::*,
+    termimad::minimad::OwningTemplateExpander,
+    std::cmp::Reverse,
+};
+
+static MD_TAGS: &str = r#"
+## tags
+|:-|:-:|:-:|:-:|:-:
+|**tag**|**hits**|**%**|**visitors**|**bytes**
+|:-|-:|-:|-:|-:
+${tags
+|${tag}|${hits}|${percent}|${visitors}|${bytes}
+}
+|-:
+"#;
+
+/// Print the hits of every tag given by the tag rules.
+///
+/// As a line may have several tags, the tables aren't built
+/// with `print_groups`, which puts every line in one group.
+pub fn print_tags(
+    log_lines: &[LogLine],
+    printer: &Printer,
+    trend_computer: Option<&TrendComputer>,
+) {
+    let mut tagged: Vec<Vec<&LogLine>> = vec![Vec::new(); printer.tag_names.len()];
+    for line in log_lines {
+        for id in line.tags.ids() {
+            tagged[id].push(line);
+        }
+    }
+    let tagged: Vec<(&str, Vec<&LogLine>)> = printer.tag_names
+        .iter()
+        .copied()
+        .zip(tagged)
+        .filter(|(_, lines)| !lines.is_empty())
+        .collect();
+    if tagged.is_empty() {
+        println!("No tagged hit");
+        return;
+    }
+    if let Some(trend_computer) = trend_computer {
+        let section = Section {
+            groups_name: "tags",
+            group_key: "tag",
+            view: View::Full,
+            changes: false,
+        };
+        let groups: Vec<LineGroup<&str>> = tagged
+            .into_iter()
+            .map(|(name, lines)| LineGroup::new(name, lines, trend_computer))
+            .sorted_unstable_by_key(|g| Reverse(g.key_sum))
+            .collect();
+        printer.print_table_with_trends("tags", &section, groups.iter(), log_lines.len());
+        return;
+    }
+    let mut expander = OwningTemplateExpander::new();
+    expander.set_default("");
+    tagged
+        .iter()
+        .map(|(name, lines)| {
+            let bytes: u64 = lines.iter().map(|line| line.bytes_sent).sum();
+            let visitors = if printer.visitors {
+                count_visitors(lines.iter().copied())
+            } else {
+                0
+            };
+            (name, lines.len(), bytes, visitors)
+        })
+        .sorted_unstable_by_key(|&(_, hits, bytes, visitors)| {
+            Reverse(printer.key.pick(hits as u64, bytes, visitors as u64))
+        })
+        .for_each(|(name, hits, bytes, visitors)| {
+            expander.sub("tags")
+                .set("tag", name)
+                .set_md("hits", printer.md_hits(hits))
+                .set("percent", to_percent(hits, log_lines.len()))
+                .set_md("visitors", printer.md_visitors(visitors))
+                .set_md("bytes", printer.md_bytes(bytes));
+        });
+    printer.print(expander, MD_TAGS);
+}
@@ -64,6 +64,18 @@ where
 {
     roots: Box<[PathBuf]>,
     filterer: Filterer,
+    /// the bot classifier, only present when bots are filtered or displayed
+    bot_classifier: Option<BotClassifier>,
+    user_agents: leak::Interner,
+    /// the scanner detector, only present when scanners are excluded or displayed
+    scanner_detector: Option<ScannerDetector>,
+    tagger: Tagger,
+    /// whether the tagger is applied to the lines
+    tagging: bool,
+    path_normalizer: Option<PathNormalizer>,
+    /// whether visitor ids are computed, and whether they include the user agent
+    visitors: bool,
+    visitor_ua: bool,
     consumer: &'c mut C,
     paths: Vec<PathBuf>,
     stop_on_error: bool,
@@ -76,6 +88,17 @@ impl<'c, C: LineConsumer> FileReader<'c, C> {
         paths: &[PathBuf],
         args: &args::Args,
         consumer: &'c mut C,
+    ) -> Result<Self, RhitError> {
+        let tagger = Tagger::new(args.tag_rules.as_deref())?;
+        Self::with_tagger(paths, args, tagger, consumer)
+    }
+    /// Build a file reader with an already built tagger, for consumers
+    /// which need the tag names before reading
+    pub fn with_tagger(
+        paths: &[PathBuf],
+        args: &args::Args,
+        tagger: Tagger,
+        consumer: &'c mut C,
     ) -> Result<Self, RhitError> {
         let check_names = !args.no_name_check;
         let roots = paths.to_vec().into_boxed_slice();
@@ -86,7 +109,22 @@ impl<'c, C: LineConsumer> FileReader<'c, C> {
         }
         let first_date = dated_files[0].0;
         let last_date = dated_files[dated_files.len()-1].0; // last first date
-        let filterer = Filterer::new(args, first_date, last_date)?;
+        let filterer = Filterer::new(args, first_date, last_date, &tagger)?;
+        let bot_classifier = if args.classifies_bots() {
+            Some(BotClassifier::new(args.bot_signatures.as_deref())?)
+        } else {
+            None
+        };
+        let scanner_detector = if args.detects_scanners() {
+            Some(ScannerDetector::new(args.scan_rules.as_deref())?)
+        } else {
+            None
+        };
+        let path_normalizer = if args.templates {
+            Some(PathNormalizer::new(&args.config.templates)?)
+        } else {
+            None
+        };
         let paths: Vec<PathBuf> = dated_files.drain(..).map(|df| df.1).collect();
         let stop_on_error = check_names;
         let silent = if args.silent_load {
@@ -102,12 +140,27 @@ impl<'c, C: LineConsumer> FileReader<'c, C> {
         Ok(Self {
             roots,
             filterer,
+            bot_classifier,
+            user_agents: leak::Interner::default(),
+            scanner_detector,
+            tagger,
+            tagging: args.tags_lines(),
+            path_normalizer,
+            visitors: args.counts_visitors(),
+            visitor_ua: args.visitor_ua,
             consumer,
             paths,
             stop_on_error,
             silent,
         })
     }
+    /// The names of the tags, indexed by their id
+    pub fn tag_names(&self) -> Vec<&'static str> {
+        self.tagger.names().to_vec()
+    }
+    pub fn tagger(&self) -> &Tagger {
+        &self.tagger
+    }
     pub fn filterer(self) -> Filterer {
         self.filterer
     }
@@ -172,8 +225,24 @@ impl<'c, C: LineConsumer> FileReader<'c, C> {
             if reader.read_line(&mut line)? == 0 {
                 break; // EOF
             }
-            match LogLine::from_str(&line) {
-                Ok(log_line) => {
+            match LogLine::parse(&line, &mut self.user_agents) {
+                Ok(mut log_line) => {
+                    if let Some(bot_classifier) = &mut self.bot_classifier {
+                        log_line.bot = bot_classifier.classify(&log_line);
+                    }
+                    if let Some(scanner_detector) = &mut self.scanner_detector {
+                        let target = LogLine::request_target(&line).unwrap_or(&log_line.path);
+                        log_line.scan = scanner_detector.detect(&log_line.remote_addr, target);
+                    }
+                    if self.tagging {
+                        log_line.tags = self.tagger.tag(&log_line);
+                    }
+                    if self.visitors {
+                        log_line.visitor = visitor_id(&log_line, self.visitor_ua);
+                    }
+                    if let Some(path_normalizer) = &self.path_normalizer {
+                        path_normalizer.apply(&mut log_line);
+                    }
                     let filtered_out = !self.filterer.accepts(&log_line);
                     self.consumer.eat_line(log_line, &line, filtered_out);
                 }
@@ -190,6 +259,7 @@ impl<'c, C: LineConsumer> FileReader<'c, C> {
                         }
                     }
                     errors += 1;
+                    self.consumer.reject_line(&line, e);
                 }
             }
         }
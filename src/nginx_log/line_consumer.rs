@@ -2,6 +2,7 @@ use {
     crate::{
         Date,
         LogLine,
+        ParseLogError,
     },
 };
 
@@ -17,6 +18,13 @@ pub trait LineConsumer {
         raw_line: &str,
         filtered_out: bool,
     );
+    /// Called for the lines which aren't valid log lines
+    fn reject_line(
+        &mut self,
+        _raw_line: &str,
+        _error: ParseLogError,
+    ) {
+    }
     fn end_eating(
         &mut self,
     ) {
@@ -1,6 +1,7 @@
 use {
     crate::*,
     std::{
+        collections::HashSet,
         path::PathBuf,
     },
 };
@@ -12,6 +13,16 @@ struct BaseContent {
     bar_idx: usize,
     unfiltered_histogram: DateHistogram,
     filtered_histogram: DateHistogram,
+    // whether visitors are counted
+    visitors: bool,
+    // visitors of the current day, and of the whole period
+    unfiltered_day_visitors: HashSet<u64>,
+    filtered_day_visitors: HashSet<u64>,
+    unfiltered_visitors: HashSet<u64>,
+    // whether hits are counted per hour, for hourly trends
+    hourly: bool,
+    // unfiltered hits per hour, the index being `24 * bar_idx + hour`
+    unfiltered_hourly_hits: Vec<u64>,
 }
 
 impl LineConsumer for BaseContent {
@@ -36,12 +47,30 @@ impl LineConsumer for BaseContent {
             ubars.push(DateBar::new(log_line.date()));
             fbars.push(DateBar::new(log_line.date()));
             self.bar_idx += 1;
+            self.unfiltered_day_visitors.clear();
+            self.filtered_day_visitors.clear();
         }
         ubars[self.bar_idx].hits += 1;
         ubars[self.bar_idx].bytes_sent += log_line.bytes_sent;
+        if self.visitors {
+            if self.unfiltered_day_visitors.insert(log_line.visitor) {
+                ubars[self.bar_idx].visitors += 1;
+            }
+            self.unfiltered_visitors.insert(log_line.visitor);
+        }
+        if self.hourly {
+            let hour_idx = 24 * self.bar_idx + log_line.hour() as usize;
+            if hour_idx >= self.unfiltered_hourly_hits.len() {
+                self.unfiltered_hourly_hits.resize(24 * (self.bar_idx + 1), 0);
+            }
+            self.unfiltered_hourly_hits[hour_idx] += 1;
+        }
         if !filtered_out {
             fbars[self.bar_idx].hits += 1;
             fbars[self.bar_idx].bytes_sent += log_line.bytes_sent;
+            if self.visitors && self.filtered_day_visitors.insert(log_line.visitor) {
+                fbars[self.bar_idx].visitors += 1;
+            }
             log_line.date_idx = self.bar_idx;
             self.lines.push(log_line);
         }
@@ -56,6 +85,14 @@ pub struct LogBase {
     pub filtered_count: u64,
     pub unfiltered_histogram: DateHistogram,
     pub unfiltered_count: u64,
+    /// whether the distinct visitors are counted, as asked by the arguments
+    pub visitors: bool,
+    /// distinct visitors of the whole period, 0 when they're not counted
+    pub unfiltered_visitors: u64,
+    /// unfiltered hits per hour, only filled for hourly trends
+    pub unfiltered_hourly_hits: Vec<u64>,
+    /// the names of the tags, indexed by their id
+    pub tag_names: Vec<&'static str>,
 }
 
 impl LogBase {
@@ -63,11 +100,26 @@ impl LogBase {
         paths: &[PathBuf],
         args: &args::Args,
     ) -> Result<Self, RhitError> {
-        let mut base_content = BaseContent::default();
+        let hourly = TrendWindows::new(args).bucket == TrendBucket::Hour;
+        let visitors = args.counts_visitors();
+        let mut base_content = BaseContent {
+            visitors,
+            hourly,
+            ..Default::default()
+        };
         let mut file_reader = FileReader::new(paths, args, &mut base_content)?;
         time!("reading files", file_reader.read_all_files())?;
+        let tag_names = file_reader.tag_names();
         let filterer = file_reader.filterer();
-        let BaseContent {lines, unfiltered_histogram, filtered_histogram, ..} = base_content;
+        let BaseContent {
+            lines,
+            unfiltered_histogram,
+            filtered_histogram,
+            unfiltered_visitors,
+            mut unfiltered_hourly_hits,
+            ..
+        } = base_content;
+        let unfiltered_visitors = unfiltered_visitors.len() as u64;
         let mut unfiltered_count = 0;
         let mut dates = Vec::new();
         for bar in &unfiltered_histogram.bars {
@@ -77,6 +129,9 @@ impl LogBase {
         if unfiltered_count == 0 {
             return Err(RhitError::NoHitInPaths(paths.to_vec()));
         }
+        if hourly {
+            unfiltered_hourly_hits.resize(24 * dates.len(), 0);
+        }
         let filtered_count = filtered_histogram.total_hits();
         Ok(Self {
             dates,
@@ -86,6 +141,10 @@ impl LogBase {
             filtered_count,
             unfiltered_histogram,
             unfiltered_count,
+            visitors,
+            unfiltered_visitors,
+            unfiltered_hourly_hits,
+            tag_names,
         })
     }
     pub fn start_time(&self) -> Date {
@@ -102,3 +161,44 @@ impl LogBase {
     }
 }
 
+#[cfg(test)]
+mod log_base_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_hourly_hits() {
+        let day = Date::new(2024, 3, 21).unwrap();
+        let next_day = day.add_days(1);
+        let lines = || [
+            LogLine::test("/").with_time("10:00:00"),
+            LogLine::test("/").with_time("10:59:59"),
+            LogLine::test("/").with_time("23:59:59"),
+            LogLine::test("/").with_date(next_day).with_time("00:00:00"),
+            LogLine::test("/").with_date(next_day).with_time("02:30:00"),
+        ];
+        let mut content = BaseContent {
+            hourly: true,
+            ..Default::default()
+        };
+        content.start_eating(day);
+        for (idx, line) in lines().into_iter().enumerate() {
+            // filtered out lines are counted too
+            content.eat_line(line, "", idx == 1);
+        }
+        let hits = &content.unfiltered_hourly_hits;
+        assert_eq!(hits.len(), 48);
+        assert_eq!(hits[10], 2);
+        assert_eq!(hits[23], 1);
+        assert_eq!(hits[24], 1);
+        assert_eq!(hits[26], 1);
+        assert_eq!(hits.iter().sum::<u64>(), 5);
+        // hits aren't counted per hour when trends are daily
+        let mut content = BaseContent::default();
+        content.start_eating(day);
+        for line in lines() {
+            content.eat_line(line, "", false);
+        }
+        assert!(content.unfiltered_hourly_hits.is_empty());
+    }
+}
@@ -7,6 +7,10 @@ use {
     thiserror::Error,
 };
 
+/// The greatest plausible `$request_time`, in seconds. Greater numbers
+/// are other values, eg a `$msec` timestamp
+const MAX_REQUEST_TIME: f32 = 3.0 * 3600.0;
+
 #[derive(Debug, Error)]
 pub enum ParseLogError {
     #[error("invalid log line {0:?}")]
@@ -32,23 +36,45 @@ pub struct LogLine {
     pub status: u16,
     pub bytes_sent: u64,
     pub referer: Box<str>,
+    /// the user agent, shared by all the lines with the same one
+    pub user_agent: &'static str,
+    pub bot: BotKind,
+    pub scan: ScanKind,
+    /// the tags given by the tag rules, set by the file reader
+    pub tags: TagSet,
+    /// the `$request_time` in seconds, when it's at the end of the line
+    pub request_time: Option<f32>,
+    /// identifier of the visitor, set by the file reader
+    pub visitor: u64,
 }
 
 impl DateIndexed for LogLine {
     fn date_idx(&self) -> usize {
         self.date_idx
     }
+    fn hour(&self) -> u8 {
+        self.date_time.time.hour
+    }
     fn bytes(&self) -> u64 {
         self.bytes_sent
     }
+    fn visitor(&self) -> u64 {
+        self.visitor
+    }
 }
 impl DateIndexed for &LogLine {
     fn date_idx(&self) -> usize {
         self.date_idx
     }
+    fn hour(&self) -> u8 {
+        self.date_time.time.hour
+    }
     fn bytes(&self) -> u64 {
         self.bytes_sent
     }
+    fn visitor(&self) -> u64 {
+        self.visitor
+    }
 }
 
 impl LogLine {
@@ -78,22 +104,107 @@ impl LogLine {
     }
 }
 
+/// Builder of log lines, for tests: `LogLine::test(path)` is a GET of
+/// the path by 1.2.3.4 on 2024/03/21 at 10:00:00, answered with a 200,
+/// and the other fields are changed with the `with_` methods
+#[cfg(test)]
+impl LogLine {
+    pub fn test(path: &str) -> Self {
+        let s = format!(
+            r#"1.2.3.4 - - [21/Mar/2024:10:00:00 +0000] "GET {} HTTP/1.1" 200 10 "-" "-""#,
+            path,
+        );
+        Self::from_str(&s).unwrap()
+    }
+    pub fn with_addr(mut self, addr: &str) -> Self {
+        self.remote_addr = addr.into();
+        self
+    }
+    pub fn with_method(mut self, method: &str) -> Self {
+        self.method = Method::from(method);
+        self
+    }
+    pub fn with_date(mut self, date: Date) -> Self {
+        self.date_time.date = date;
+        self
+    }
+    /// Set the time of the day, eg `10:05:00`
+    pub fn with_time(mut self, time: &str) -> Self {
+        let s = format!("21/Mar/2024:{} +0000", time);
+        self.date_time.time = DateTime::from_nginx(&s).unwrap().time;
+        self
+    }
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+    pub fn with_referer(mut self, referer: &str) -> Self {
+        self.referer = referer.into();
+        self
+    }
+    pub fn with_user_agent(mut self, user_agent: &'static str) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+    pub fn with_request_time(mut self, request_time: f32) -> Self {
+        self.request_time = Some(request_time);
+        self
+    }
+}
+
+/// Split the request of a log line in its method and its target
+/// (the path and the query string)
+fn split_request(request: &str) -> (Method, &str) {
+    let mut tokens = request.split(' ');
+    match (tokens.next(), tokens.next()) {
+        (Some(method), Some(target)) => (Method::from(method), target),
+        (Some(target), None) => (Method::None, target),
+        _ => unreachable!(),
+    }
+}
+
 impl FromStr for LogLine {
     type Err = ParseLogError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &mut leak::Interner::default())
+    }
+}
+
+impl LogLine {
+    /// Return the request target (path and query string) of a raw
+    /// log line, without parsing the rest of the line
+    pub fn request_target(s: &str) -> Option<&str> {
+        let mut ranger = Ranger::new(s);
+        ranger.until(' ').ok()?;
+        ranger.between('[', ']').ok()?;
+        let (_, target) = split_request(ranger.between('"', '"').ok()?);
+        Some(target)
+    }
+    /// Parse a log line, with its user agent taken from (or added to)
+    /// the given interner
+    pub fn parse(s: &str, user_agents: &mut leak::Interner) -> Result<Self, ParseLogError> {
         let mut ranger = Ranger::new(s);
         let remote_addr = ranger.until(' ')?.into();
         let date_time = DateTime::from_nginx(ranger.between('[', ']')?)?;
-        let mut request = ranger.between('"', '"')?.split(' ');
-        let (method, path) = match (request.next(), request.next()) {
-            (Some(method), Some(path)) => (Method::from(method), path),
-            (Some(path), None) => (Method::None, path),
-            _ => unreachable!(),
-        };
-        let path = path.split('?').next().unwrap().into();
+        let (method, target) = split_request(ranger.between('"', '"')?);
+        let path = target.split('?').next().unwrap().into();
         let status = ranger.between(' ', ' ')?.parse()?;
         let bytes_sent = ranger.between(' ', ' ')?.parse()?;
         let referer = ranger.between('"', '"')?.into();
+        let user_agent = ranger.next_between('"', '"');
+        // the request time, if logged, is usually the first number with
+        // a decimal point following the user agent. Numbers which can't be
+        // durations of requests (negative, or timestamps) are skipped
+        let request_time = match user_agent {
+            Ok(_) => ranger
+                .rest()
+                .split_whitespace()
+                .filter(|token| token.contains('.'))
+                .filter_map(|token| token.parse::<f32>().ok())
+                .find(|&t| (0.0..=MAX_REQUEST_TIME).contains(&t)),
+            Err(_) => None,
+        };
+        let user_agent = user_agents.intern(user_agent.unwrap_or_default());
         Ok(LogLine {
             remote_addr,
             date_time,
@@ -103,6 +214,12 @@ impl FromStr for LogLine {
             status,
             bytes_sent,
             referer,
+            user_agent,
+            bot: BotKind::Human,
+            scan: ScanKind::Clean,
+            tags: TagSet::default(),
+            request_time,
+            visitor: 0,
         })
     }
 }
@@ -122,6 +239,32 @@ mod log_line_parsing_tests {
         assert_eq!(ll.status, 200);
         assert_eq!(ll.bytes_sent, 99);
         assert_eq!(&*ll.referer, "https://miaou.dystroy.org/3");
+        assert!(ll.user_agent.starts_with("Mozilla/5.0 (X11; Linux x86_64)"));
+        assert_eq!(ll.request_time, None);
+    }
+
+    #[test]
+    fn user_agents_are_shared() {
+        let mut user_agents = leak::Interner::default();
+        let a = LogLine::parse(SIO_PULL_LINE, &mut user_agents).unwrap();
+        let b = LogLine::parse(SIO_PULL_LINE, &mut user_agents).unwrap();
+        assert!(std::ptr::eq(a.user_agent, b.user_agent));
+    }
+
+    static REQUEST_TIME_LINE: &str = r#"203.0.113.4 - - [01/Mar/2024:00:03:27 +0000] "GET /user/456/profile HTTP/1.1" 200 45437 "https://dystroy.org/" "Mozilla/5.0 (compatible; bingbot/2.0)" "-" 0.155 0.150"#;
+    #[test]
+    fn parse_request_time_line() {
+        let ll = LogLine::from_str(REQUEST_TIME_LINE).unwrap();
+        assert_eq!(ll.user_agent, "Mozilla/5.0 (compatible; bingbot/2.0)");
+        assert_eq!(ll.request_time, Some(0.155));
+    }
+
+    static MSEC_LINE: &str = r#"203.0.113.4 - - [21/Mar/2024:00:00:07 +0000] "GET / HTTP/1.1" 200 45437 "-" "curl/8.0" 1710979207.123 -1.0 0.042"#;
+    #[test]
+    fn parse_msec_line() {
+        // the timestamp and the negative number aren't request times
+        let ll = LogLine::from_str(MSEC_LINE).unwrap();
+        assert_eq!(ll.request_time, Some(0.042));
     }
 
     static NO_VERB_LINE: &str = r#"119.142.145.250 - - [10/Jan/2021:10:27:01 +0000] "\x16\x03\x01\x00u\x01\x00\x00q\x03\x039a\xDF\xCA\x90\xB1\xB4\xC2SB\x96\xF0\xB7\x96CJD\xE1\xBF\x0E\xE1Y\xA2\x87v\x1D\xED\xBDo\x05A\x9D\x00\x00\x1A\xC0/\xC0+\xC0\x11\xC0\x07\xC0\x13\xC0\x09\xC0\x14\xC0" 400 173 "-" "-""#;
@@ -132,6 +275,14 @@ mod log_line_parsing_tests {
         assert_eq!(ll.status, 400);
         assert_eq!(ll.bytes_sent, 173);
     }
+    #[test]
+    fn request_targets() {
+        assert_eq!(
+            LogLine::request_target(SIO_PULL_LINE),
+            Some("/socket.io/?EIO=3&transport=polling&t=NSd_nu-"),
+        );
+        assert!(LogLine::request_target(NO_VERB_LINE).unwrap().starts_with(r"\x16\x03"));
+    }
 
 
     static ISSUE_3_LINE: &str = r#"0.0.0.0 - - [2021-03-03T09:08:37+08:00] "GET /zhly/assets/guide/audit-opinion.png HTTP/1.1" 200 3911 "http://0.0.0.0:8091/zhly/" "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/90.0.4427.5 Safari/537.36" "-""#;
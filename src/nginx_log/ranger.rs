@@ -31,6 +31,12 @@ impl<'s> Ranger<'s> {
         }
         Err(ParseLogError::CharNotFound(end))
     }
+    /// Same as `between` but without considering the last found end
+    /// char as a possible start (useful for consecutive quoted fields)
+    pub fn next_between(&mut self, start: char, end: char) -> Result<&'s str, ParseLogError> {
+        self.last = None;
+        self.between(start, end)
+    }
     pub fn between(&mut self, start: char, end: char) -> Result<&'s str, ParseLogError> {
         if Some(start) == self.last {
             self.pos += start.len_utf8();
@@ -51,4 +57,9 @@ impl<'s> Ranger<'s> {
         }
         Err(ParseLogError::CharNotFound(start))
     }
+    /// Return what follows the last found char
+    pub fn rest(&self) -> &'s str {
+        let start = self.pos + self.last.map_or(0, |c| c.len_utf8());
+        self.s.get(start..).unwrap_or("")
+    }
 }
@@ -16,6 +16,13 @@ pub enum Output {
     Csv,
     /// An array of log objects
     Json,
+    /// The remote addresses of the accepted lines, to block them
+    Blocklist,
+    /// The days or hours with unusual hits, as a JSON array
+    Anomalies,
+    /// Log-scaled histograms of hits per IP, response sizes and
+    /// hits per path, as a JSON object
+    Distributions,
 }
 
 #[derive(Debug, Error)]
@@ -32,6 +39,9 @@ impl FromStr for Output {
             "t" | "tbl" | "tables" => Ok(Self::Tables),
             "c" | "csv" => Ok(Self::Csv),
             "j" | "json" => Ok(Self::Json),
+            "b" | "blocklist" => Ok(Self::Blocklist),
+            "anomalies" => Ok(Self::Anomalies),
+            "distributions" | "distrib" => Ok(Self::Distributions),
             _ => Err(ParseOutputError::UnrecognizedValue(value.to_owned()))
         }
     }
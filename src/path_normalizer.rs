@@ -0,0 +1,245 @@
+use {
+    crate::*,
+    lazy_regex::regex::{self, Regex},
+    serde::Deserialize,
+    std::borrow::Cow,
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum PathTemplateError {
+    #[error("invalid path template pattern {0:?}: {1}")]
+    InvalidPattern(String, regex::Error),
+}
+
+/// A user defined rule: paths matching the pattern are replaced
+/// with the template, which may refer to capture groups (eg `$1`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathTemplateRule {
+    pub pattern: String,
+    pub template: String,
+}
+
+/// Configuration of path normalization, used with `--templates`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PathTemplatesConfig {
+    /// whether to replace ids, uuids, hashes and dates in segments
+    pub auto: bool,
+    /// whether to decode percent-encoded chars (eg `%20`)
+    pub decode_percent: bool,
+    /// whether to remove the trailing slash (`/blog/` becomes `/blog`)
+    pub unify_trailing_slash: bool,
+    pub rules: Vec<PathTemplateRule>,
+}
+
+impl Default for PathTemplatesConfig {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            decode_percent: false,
+            unify_trailing_slash: false,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Turns paths into route templates, eg `/user/123` into `/user/{id}`,
+/// so that they can be grouped
+pub struct PathNormalizer {
+    auto: bool,
+    decode_percent: bool,
+    unify_trailing_slash: bool,
+    rules: Vec<(Regex, String)>,
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_hash(s: &str) -> bool {
+    s.len() >= 16
+        && s.chars().all(|c| c.is_ascii_hexdigit())
+        && s.chars().any(|c| c.is_ascii_digit())
+}
+
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    let digits = |r: std::ops::Range<usize>| b[r].iter().all(u8::is_ascii_digit);
+    // year, month and day are checked so that ids aren't taken for dates
+    let valid = |year: &str, month: &str, day: &str| {
+        is_year(year)
+            && matches!(month.parse::<u8>(), Ok(1..=12))
+            && matches!(day.parse::<u8>(), Ok(1..=31))
+    };
+    match b.len() {
+        8 => digits(0..8) && valid(&s[0..4], &s[4..6], &s[6..8]), // 20240301
+        10 => digits(0..4) && digits(5..7) && digits(8..10)
+            && b[4] == b[7] && (b[4] == b'-' || b[4] == b'_')
+            && valid(&s[0..4], &s[5..7], &s[8..10]), // 2024-03-01
+        _ => false,
+    }
+}
+
+fn is_year(s: &str) -> bool {
+    s.len() == 4 && (s.starts_with("19") || s.starts_with("20"))
+}
+
+/// Return the template of a path segment, if it's recognized
+fn segment_template(segment: &str) -> Option<&'static str> {
+    if segment.is_empty() {
+        None
+    } else if segment.chars().all(|c| c.is_ascii_digit()) {
+        if is_date(segment) {
+            Some("{date}")
+        } else if is_year(segment) {
+            Some("{year}")
+        } else {
+            Some("{id}")
+        }
+    } else if is_uuid(segment) {
+        Some("{uuid}")
+    } else if is_hash(segment) {
+        Some("{hash}")
+    } else if is_date(segment) {
+        Some("{date}")
+    } else {
+        None
+    }
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decode the `%XX` sequences, when the result is valid UTF-8
+pub fn percent_decode(s: &str) -> Cow<'_, str> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s);
+    }
+    let b = s.as_bytes();
+    let mut bytes = Vec::with_capacity(b.len());
+    let mut decoded = false;
+    let mut i = 0;
+    while i < b.len() {
+        if b[i] == b'%' && i + 2 < b.len() {
+            if let (Some(h), Some(l)) = (hex_value(b[i + 1]), hex_value(b[i + 2])) {
+                bytes.push(h * 16 + l);
+                decoded = true;
+                i += 3;
+                continue;
+            }
+        }
+        bytes.push(b[i]);
+        i += 1;
+    }
+    match String::from_utf8(bytes) {
+        Ok(s) if decoded => Cow::Owned(s),
+        _ => Cow::Borrowed(s),
+    }
+}
+
+impl PathNormalizer {
+    pub fn new(config: &PathTemplatesConfig) -> Result<Self, PathTemplateError> {
+        let rules = config.rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.template.clone()))
+                    .map_err(|e| PathTemplateError::InvalidPattern(rule.pattern.clone(), e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            auto: config.auto,
+            decode_percent: config.decode_percent,
+            unify_trailing_slash: config.unify_trailing_slash,
+            rules,
+        })
+    }
+    /// Return the template of the path, or None if it's unchanged
+    pub fn normalize(&self, path: &str) -> Option<String> {
+        let mut path = Cow::Borrowed(path);
+        if self.decode_percent {
+            if let Cow::Owned(decoded) = percent_decode(&path) {
+                path = Cow::Owned(decoded);
+            }
+        }
+        if self.unify_trailing_slash && path.len() > 1 && path.ends_with('/') {
+            path = Cow::Owned(path[..path.len() - 1].to_owned());
+        }
+        for (regex, template) in &self.rules {
+            if regex.is_match(&path) {
+                return Some(regex.replace(&path, template.as_str()).into_owned());
+            }
+        }
+        if self.auto && path.split('/').any(|s| segment_template(s).is_some()) {
+            let templated: Vec<&str> = path
+                .split('/')
+                .map(|segment| segment_template(segment).unwrap_or(segment))
+                .collect();
+            return Some(templated.join("/"));
+        }
+        match path {
+            Cow::Owned(path) => Some(path),
+            Cow::Borrowed(_) => None,
+        }
+    }
+    /// Replace the path of the line with its template
+    pub fn apply(&self, line: &mut LogLine) {
+        if let Some(path) = self.normalize(&line.path) {
+            line.path = path.into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod path_normalizer_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_auto_templates() {
+        let pn = PathNormalizer::new(&PathTemplatesConfig::default()).unwrap();
+        assert_eq!(pn.normalize("/user/123").as_deref(), Some("/user/{id}"));
+        assert_eq!(pn.normalize("/user/456/profile").as_deref(), Some("/user/{id}/profile"));
+        assert_eq!(
+            pn.normalize("/api/items/9f8e7d6c-1234-4abc-9def-0123456789ab").as_deref(),
+            Some("/api/items/{uuid}"),
+        );
+        assert_eq!(
+            pn.normalize("/static/app.js/0123456789abcdef0123").as_deref(),
+            Some("/static/app.js/{hash}"),
+        );
+        assert_eq!(pn.normalize("/blog/2024/rust").as_deref(), Some("/blog/{year}/rust"));
+        assert_eq!(pn.normalize("/news/2024-03-01/").as_deref(), Some("/news/{date}/"));
+        assert_eq!(pn.normalize("/archive/20240301").as_deref(), Some("/archive/{date}"));
+        // 8 digit ids which aren't dates
+        assert_eq!(pn.normalize("/order/29999999").as_deref(), Some("/order/{id}"));
+        assert_eq!(pn.normalize("/order/10000001").as_deref(), Some("/order/{id}"));
+        assert_eq!(pn.normalize("/order/20241345").as_deref(), Some("/order/{id}"));
+        assert_eq!(pn.normalize("/blog/rust"), None);
+        assert_eq!(pn.normalize("/"), None);
+    }
+
+    #[test]
+    fn test_rules_and_options() {
+        let pn = PathNormalizer::new(&PathTemplatesConfig {
+            auto: true,
+            decode_percent: true,
+            unify_trailing_slash: true,
+            rules: vec![PathTemplateRule {
+                pattern: "^/@([^/]+)$".to_owned(),
+                template: "/@{user}".to_owned(),
+            }],
+        }).unwrap();
+        assert_eq!(pn.normalize("/@dystroy").as_deref(), Some("/@{user}"));
+        assert_eq!(pn.normalize("/blog/").as_deref(), Some("/blog"));
+        assert_eq!(pn.normalize("/"), None);
+        assert_eq!(pn.normalize("/caf%C3%A9").as_deref(), Some("/café"));
+        assert_eq!(pn.normalize("/tag%2").as_deref(), None);
+    }
+}
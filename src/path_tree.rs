@@ -0,0 +1,142 @@
+use {
+    crate::*,
+    std::collections::HashMap,
+};
+
+/// A directory in the tree of paths, with the lines of all
+/// the paths it contains
+pub struct PathTreeNode<'b> {
+    pub dir: &'b str,
+    pub depth: usize,
+    pub lines: Vec<&'b LogLine>,
+    pub bytes: u64,
+    pub children: Vec<PathTreeNode<'b>>,
+}
+
+/// Iterate over the directory prefixes of a path, up to the given
+/// depth: `/blog/2024/rust` gives `/`, `/blog/`, and `/blog/2024/`
+fn dir_prefixes(path: &str, max_depth: usize) -> impl Iterator<Item = (usize, &str)> {
+    path.match_indices('/')
+        .take(max_depth + 1)
+        .enumerate()
+        .map(move |(depth, (idx, _))| (depth, &path[..=idx]))
+}
+
+impl<'b> PathTreeNode<'b> {
+    /// Build the tree of directories of the lines' paths, in one pass.
+    ///
+    /// Paths not starting with `/` (eg proxy requests like
+    /// `http://host/x`) aren't in the tree
+    pub fn build<I>(lines: I, max_depth: usize) -> Self
+    where
+        I: Iterator<Item = &'b LogLine>,
+    {
+        let mut dirs: HashMap<&'b str, (usize, Vec<&'b LogLine>)> = HashMap::new();
+        for line in lines.filter(|line| line.path.starts_with('/')) {
+            for (depth, dir) in dir_prefixes(&line.path, max_depth) {
+                dirs.entry(dir)
+                    .or_insert_with(|| (depth, Vec::new()))
+                    .1.push(line);
+            }
+        }
+        let mut nodes: Vec<Self> = dirs
+            .into_iter()
+            .map(|(dir, (depth, lines))| {
+                let bytes = lines.iter().map(|line| line.bytes_sent).sum();
+                Self { dir, depth, lines, bytes, children: Vec::new() }
+            })
+            .collect();
+        // we attach nodes to their parents, shallowest first, so that
+        // the parent of a node is always already in the tree
+        nodes.sort_unstable_by_key(|node| std::cmp::Reverse(node.depth));
+        let mut root = Self {
+            dir: "/",
+            depth: 0,
+            lines: Vec::new(),
+            bytes: 0,
+            children: Vec::new(),
+        };
+        while let Some(node) = nodes.pop() {
+            if node.depth == 0 {
+                root.lines = node.lines;
+                root.bytes = node.bytes;
+                continue;
+            }
+            let parent_dir = &node.dir[..node.dir[..node.dir.len() - 1].rfind('/').unwrap() + 1];
+            match root.find_mut(parent_dir) {
+                Some(parent) => parent.children.push(node),
+                None => root.children.push(node), // not expected
+            }
+        }
+        root
+    }
+    fn find_mut(&mut self, dir: &str) -> Option<&mut Self> {
+        if self.dir == dir {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .filter(|child| dir.starts_with(child.dir))
+            .find_map(|child| child.find_mut(dir))
+    }
+    pub fn hits(&self) -> usize {
+        self.lines.len()
+    }
+    pub fn key_sum(&self, key: Key) -> u64 {
+        match key {
+            Key::Hits => self.lines.len() as u64,
+            Key::Bytes => self.bytes,
+            Key::Visitors => count_visitors(self.lines.iter().copied()) as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod path_tree_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_dir_prefixes() {
+        let prefixes: Vec<_> = dir_prefixes("/blog/2024/rust", 5).collect();
+        assert_eq!(prefixes, vec![(0, "/"), (1, "/blog/"), (2, "/blog/2024/")]);
+        let prefixes: Vec<_> = dir_prefixes("/blog/2024/rust", 1).collect();
+        assert_eq!(prefixes, vec![(0, "/"), (1, "/blog/")]);
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let lines = [
+            LogLine::test("/"),
+            LogLine::test("/blog/"),
+            LogLine::test("/blog/2024/rust"),
+            LogLine::test("/blog/2024/nginx"),
+            LogLine::test("/blog/2023/old"),
+            LogLine::test("/download/broot"),
+        ];
+        let root = PathTreeNode::build(lines.iter(), 3);
+        assert_eq!(root.hits(), 6);
+        assert_eq!(root.bytes, 60);
+        assert_eq!(root.children.len(), 2);
+        let blog = root.children.iter().find(|n| n.dir == "/blog/").unwrap();
+        assert_eq!(blog.hits(), 4);
+        assert_eq!(blog.children.len(), 2);
+        let y2024 = blog.children.iter().find(|n| n.dir == "/blog/2024/").unwrap();
+        assert_eq!(y2024.hits(), 2);
+        assert_eq!(y2024.depth, 2);
+    }
+
+    #[test]
+    fn test_build_tree_without_leading_slash() {
+        let lines = [
+            LogLine::test("/blog/rust"),
+            LogLine::test("/blog/nginx"),
+            LogLine::test("http://example.com/x"),
+            LogLine::test("a/b"),
+        ];
+        let root = PathTreeNode::build(lines.iter(), 3);
+        assert_eq!(root.hits(), 2);
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].dir, "/blog/");
+    }
+}
@@ -0,0 +1,253 @@
+use {
+    crate::*,
+    itertools::Itertools,
+    std::collections::HashMap,
+};
+
+/// Percentiles of the hits per second of a period, counting
+/// the seconds without hit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RatePercentiles {
+    pub p50: u32,
+    pub p95: u32,
+    pub p99: u32,
+    pub max: u32,
+}
+
+impl RatePercentiles {
+    /// Compute the percentiles from the counts of the seconds with
+    /// hits, and the total number of seconds of the period
+    pub fn from_counts(mut counts: Vec<u32>, seconds: usize) -> Self {
+        counts.sort_unstable();
+        let seconds = seconds.max(counts.len());
+        let zeros = seconds - counts.len();
+        let percentile = |p: f64| {
+            let rank = (p * seconds as f64).ceil() as usize;
+            if rank <= zeros {
+                0
+            } else {
+                counts[rank - zeros - 1]
+            }
+        };
+        Self {
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: counts.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Return the number of seconds of a period starting at `start` and
+/// lasting `duration` seconds, whose first and last hits are at the given
+/// seconds, the period being counted from its start unless `from_first`,
+/// and up to its end unless `to_last`
+fn period_seconds(
+    (start, duration): (i64, i64),
+    (first, last): (i64, i64),
+    from_first: bool,
+    to_last: bool,
+) -> usize {
+    let end = if to_last { last } else { start + duration - 1 };
+    let start = if from_first { first } else { start };
+    (end - start + 1) as usize
+}
+
+/// Compute the rate percentiles of every day, indexed like `base.dates`.
+///
+/// The first and last days of the logs are usually partial, so they're
+/// counted from their first hit, or up to their last one. When a time
+/// or date filter may cut the days, they're all counted from their first
+/// hit to their last one.
+pub fn daily_rate_percentiles(base: &LogBase) -> Vec<RatePercentiles> {
+    let mut day_counts: Vec<HashMap<u32, u32>> = vec![HashMap::new(); base.dates.len()];
+    for line in &base.lines {
+        *day_counts[line.date_idx].entry(line.time().seconds()).or_default() += 1;
+    }
+    let cut = base.filterer.has_time_filter() || base.filterer.has_date_filter();
+    let last_idx = day_counts.len().saturating_sub(1);
+    day_counts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, counts)| {
+            let seconds = match counts.keys().minmax().into_option() {
+                Some((&first, &last)) => {
+                    period_seconds(
+                        (0, 86400),
+                        (first as i64, last as i64),
+                        cut || idx == 0,
+                        cut || idx == last_idx,
+                    )
+                }
+                None => 86400,
+            };
+            RatePercentiles::from_counts(counts.into_values().collect(), seconds)
+        })
+        .collect()
+}
+
+/// Compute the rate percentiles of the buckets from `first_idx` to
+/// `last_idx`, counted like the days of [daily_rate_percentiles]
+pub fn bucket_rate_percentiles(
+    base: &LogBase,
+    bucket: TimeBucket,
+    first_idx: i64,
+    last_idx: i64,
+) -> Vec<RatePercentiles> {
+    let mut bucket_counts: Vec<HashMap<i64, u32>> =
+        vec![HashMap::new(); (last_idx - first_idx + 1) as usize];
+    for line in &base.lines {
+        let idx = (bucket.index(line.date_time) - first_idx) as usize;
+        *bucket_counts[idx].entry(line.date_time.timestamp()).or_default() += 1;
+    }
+    let cut = base.filterer.has_time_filter() || base.filterer.has_date_filter();
+    let last = bucket_counts.len().saturating_sub(1);
+    bucket_counts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, counts)| {
+            let span = bucket.span(first_idx + idx as i64);
+            let seconds = match counts.keys().minmax().into_option() {
+                Some((&first_hit, &last_hit)) => {
+                    period_seconds(span, (first_hit, last_hit), cut || idx == 0, cut || idx == last)
+                }
+                None => span.1 as usize,
+            };
+            RatePercentiles::from_counts(counts.into_values().collect(), seconds)
+        })
+        .collect()
+}
+
+/// Compute the rate percentiles over the seconds between the first
+/// and the last hits
+pub fn rate_percentiles(lines: &[LogLine]) -> RatePercentiles {
+    let mut counts: HashMap<i64, u32> = HashMap::new();
+    for line in lines {
+        *counts.entry(line.date_time.timestamp()).or_default() += 1;
+    }
+    let seconds = match counts.keys().minmax().into_option() {
+        Some((first, last)) => (last - first + 1) as usize,
+        None => 0,
+    };
+    RatePercentiles::from_counts(counts.into_values().collect(), seconds)
+}
+
+/// A window of time (a second or a minute) with many hits
+#[derive(Debug, Clone)]
+pub struct Peak {
+    pub start: DateTime,
+    /// duration of the window, in seconds
+    pub duration: i64,
+    pub hits: usize,
+    /// the most frequent path, with its hits
+    pub top_path: (String, usize),
+    /// the most frequent remote address, with its hits
+    pub top_ip: (String, usize),
+    /// the mean number of requests being served during the window,
+    /// estimated from their `$request_time` (Little's law)
+    pub concurrency: Option<f64>,
+}
+
+impl Peak {
+    pub fn rate(&self) -> f64 {
+        self.hits as f64 / self.duration as f64
+    }
+}
+
+fn most_frequent<'l, I: Iterator<Item = &'l str>>(values: I) -> (String, usize) {
+    values
+        .counts()
+        .into_iter()
+        .max_by_key(|&(value, count)| (count, std::cmp::Reverse(value)))
+        .map(|(value, count)| (value.to_owned(), count))
+        .unwrap_or_default()
+}
+
+/// Return the windows of the given duration with the most hits,
+/// the busiest first
+pub fn find_peaks(lines: &[LogLine], duration: i64, limit: usize) -> Vec<Peak> {
+    let mut windows: HashMap<i64, Vec<&LogLine>> = HashMap::new();
+    for line in lines {
+        windows
+            .entry(line.date_time.timestamp().div_euclid(duration))
+            .or_default()
+            .push(line);
+    }
+    windows
+        .into_iter()
+        .sorted_unstable_by_key(|(window, lines)| (std::cmp::Reverse(lines.len()), *window))
+        .take(limit)
+        .map(|(window, lines)| {
+            let request_times: Vec<f32> = lines
+                .iter()
+                .filter_map(|line| line.request_time)
+                .collect();
+            let concurrency = if request_times.is_empty() {
+                None
+            } else {
+                // lines without request time are counted at the mean one
+                let mean = request_times.iter().sum::<f32>() as f64 / request_times.len() as f64;
+                Some(mean * lines.len() as f64 / duration as f64)
+            };
+            Peak {
+                start: DateTime::from_timestamp(window * duration),
+                duration,
+                hits: lines.len(),
+                top_path: most_frequent(lines.iter().map(|line| &*line.path)),
+                top_ip: most_frequent(lines.iter().map(|line| &*line.remote_addr)),
+                concurrency,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod peaks_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rate_percentiles() {
+        // 100 seconds, 90 of them without hit
+        let rates = RatePercentiles::from_counts(vec![1, 1, 1, 1, 1, 2, 2, 3, 5, 9], 100);
+        assert_eq!(rates, RatePercentiles { p50: 0, p95: 1, p99: 5, max: 9 });
+        let rates = RatePercentiles::from_counts(vec![], 100);
+        assert_eq!(rates, RatePercentiles::default());
+    }
+
+    #[test]
+    fn test_period_seconds() {
+        let day = (0, 86400);
+        assert_eq!(period_seconds(day, (3600, 7199), false, false), 86400);
+        // a log starting at 18h
+        assert_eq!(period_seconds(day, (64800, 86399), true, false), 21600);
+        // a log ending at 6h
+        assert_eq!(period_seconds(day, (0, 21599), false, true), 21600);
+        // a day cut by a time filter
+        assert_eq!(period_seconds(day, (36000, 43199), true, true), 7200);
+        // an hour bucket, the log starting at its 15th minute
+        assert_eq!(period_seconds((7200, 3600), (8100, 9000), true, false), 2700);
+    }
+
+    #[test]
+    fn test_find_peaks() {
+        let lines = vec![
+            LogLine::test("/a").with_time("10:00:01").with_request_time(0.5),
+            LogLine::test("/b").with_time("10:00:01").with_request_time(1.5),
+            LogLine::test("/b").with_time("10:00:01"),
+            LogLine::test("/a").with_time("10:00:02").with_request_time(0.1),
+            LogLine::test("/c").with_time("10:01:30"),
+        ];
+        let peaks = find_peaks(&lines, 1, 2);
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0].start.to_string(), "2024/03/21T10:00:01");
+        assert_eq!(peaks[0].hits, 3);
+        assert_eq!(peaks[0].top_path, ("/b".to_owned(), 2));
+        assert_eq!(peaks[0].concurrency, Some(3.0));
+        let peaks = find_peaks(&lines, 60, 10);
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0].hits, 4);
+        assert_eq!(peaks[1].concurrency, None);
+        assert_eq!(rate_percentiles(&lines).max, 3);
+    }
+}
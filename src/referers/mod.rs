@@ -0,0 +1,7 @@
+mod referer_category;
+mod referer_classifier;
+
+pub use {
+    referer_category::*,
+    referer_classifier::*,
+};
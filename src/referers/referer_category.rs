@@ -0,0 +1,35 @@
+use {
+    std::fmt,
+};
+
+/// The kind of source a hit comes from, according to its referer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefererCategory {
+    /// no referer
+    Direct,
+    /// a page of one of our own domains
+    Internal,
+    /// a search engine
+    Search,
+    /// a social network or link aggregator
+    Social,
+    Other,
+}
+
+impl RefererCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Internal => "internal",
+            Self::Search => "search",
+            Self::Social => "social",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for RefererCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,199 @@
+use {
+    crate::*,
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+        net::IpAddr,
+    },
+};
+
+/// The embedded list of known referer sources
+static SOURCES: &str = include_str!("sources.txt");
+
+/// Second level labels under which domains are registered in
+/// some country code top level domains, eg `co` in `bbc.co.uk`
+static SECOND_LEVEL_LABELS: &[&str] = &[
+    "ac", "co", "com", "edu", "gov", "ne", "net", "or", "org",
+];
+
+/// Return the host of a referer URL, or None when there's
+/// no referer (an empty one or `-`)
+pub fn referer_host(referer: &str) -> Option<&str> {
+    if referer.len() < 2 {
+        return None;
+    }
+    let s = match referer.find("://") {
+        Some(idx) => &referer[idx + 3..],
+        None => referer,
+    };
+    let end = s.find(['/', '?', '#']).unwrap_or(s.len());
+    let s = &s[..end];
+    let s = match s.rfind('@') {
+        Some(idx) => &s[idx + 1..],
+        None => s,
+    };
+    let s = match s.rfind(':') {
+        Some(idx) if !s.ends_with(']') => &s[..idx],
+        _ => s,
+    };
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Return the host in lowercase, as host names are case insensitive,
+/// without allocating when it's already lowercase
+pub fn lowercase_host(host: &str) -> Cow<'_, str> {
+    if host.bytes().any(|b| b.is_ascii_uppercase()) {
+        host.to_ascii_lowercase().into()
+    } else {
+        host.into()
+    }
+}
+
+/// Return the registrable part of a host, eg `example.co.uk`
+/// for `blog.example.co.uk`, or `example.com` for `www.example.com`.
+///
+/// This is an approximation which doesn't use the public suffix list.
+pub fn registrable_domain(host: &str) -> &str {
+    if host.parse::<IpAddr>().is_ok() {
+        return host;
+    }
+    let labels: Vec<(usize, &str)> = host
+        .match_indices('.')
+        .map(|(idx, _)| (idx, &host[idx + 1..]))
+        .collect();
+    let mut kept = 2;
+    if labels.len() >= 2 {
+        let tld = labels[labels.len() - 1].1;
+        let second = labels[labels.len() - 2].1;
+        let second = &second[..second.len() - tld.len() - 1];
+        if tld.len() == 2 && SECOND_LEVEL_LABELS.contains(&second) {
+            kept = 3;
+        }
+    }
+    if labels.len() < kept {
+        host
+    } else {
+        &host[labels[labels.len() - kept].0 + 1..]
+    }
+}
+
+/// Tell whether the host is the domain or one of its subdomains
+fn is_in_domain(host: &str, domain: &str) -> bool {
+    host.strip_suffix(domain)
+        .map_or(false, |start| start.is_empty() || start.ends_with('.'))
+}
+
+/// Classifies referers as search engines, social networks, internal
+/// links (from our own domains), direct hits (no referer) or other
+pub struct RefererClassifier {
+    own_domains: Vec<String>,
+    domains: HashMap<&'static str, RefererCategory>,
+    names: HashMap<&'static str, RefererCategory>,
+}
+
+impl RefererClassifier {
+    /// Build a classifier from the embedded list of sources, and the
+    /// domains considered as internal
+    pub fn new(own_domains: Vec<String>) -> Self {
+        let mut domains = HashMap::new();
+        let mut names = HashMap::new();
+        for line in SOURCES.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (category, source) = match line.split_once(char::is_whitespace) {
+                Some(("search", source)) => (RefererCategory::Search, source.trim()),
+                Some(("social", source)) => (RefererCategory::Social, source.trim()),
+                _ => {
+                    warn!("invalid referer source line {:?}", line);
+                    continue;
+                }
+            };
+            if source.contains('.') {
+                domains.insert(source, category);
+            } else {
+                names.insert(source, category);
+            }
+        }
+        let own_domains = own_domains
+            .iter()
+            .map(|domain| domain.to_ascii_lowercase())
+            .collect();
+        Self { own_domains, domains, names }
+    }
+    pub fn classify_host(&self, host: &str) -> RefererCategory {
+        let host = lowercase_host(host);
+        let host = &*host;
+        if self.own_domains.iter().any(|domain| is_in_domain(host, domain)) {
+            return RefererCategory::Internal;
+        }
+        // we look for the host and its parent domains
+        let mut domain = host;
+        loop {
+            if let Some(&category) = self.domains.get(domain) {
+                return category;
+            }
+            match domain.split_once('.') {
+                Some((_, parent)) => domain = parent,
+                None => break,
+            }
+        }
+        let registrable = registrable_domain(host);
+        let name = registrable.split('.').next().unwrap_or(registrable);
+        if let Some(&category) = self.names.get(name) {
+            return category;
+        }
+        RefererCategory::Other
+    }
+    pub fn classify(&self, referer: &str) -> RefererCategory {
+        match referer_host(referer) {
+            Some(host) => self.classify_host(host),
+            None => RefererCategory::Direct,
+        }
+    }
+}
+
+#[cfg(test)]
+mod referer_classifier_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_referer_host() {
+        assert_eq!(referer_host("-"), None);
+        assert_eq!(referer_host("https://www.google.com/search?q=rhit"), Some("www.google.com"));
+        assert_eq!(referer_host("http://dystroy.org:8080"), Some("dystroy.org"));
+        assert_eq!(referer_host("android-app://com.google.android.gm/"), Some("com.google.android.gm"));
+        assert_eq!(referer_host("https://[::1]/a"), Some("[::1]"));
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("www.example.com"), "example.com");
+        assert_eq!(registrable_domain("blog.example.co.uk"), "example.co.uk");
+        assert_eq!(registrable_domain("example.co.uk"), "example.co.uk");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("1.2.3.4"), "1.2.3.4");
+    }
+
+    #[test]
+    fn test_classify() {
+        let rc = RefererClassifier::new(vec!["dystroy.org".to_owned()]);
+        assert_eq!(rc.classify("-"), RefererCategory::Direct);
+        assert_eq!(rc.classify("https://dystroy.org/blog"), RefererCategory::Internal);
+        assert_eq!(rc.classify("https://Blog.Dystroy.ORG/"), RefererCategory::Internal);
+        assert_eq!(rc.classify("https://WWW.Google.com/"), RefererCategory::Search);
+        assert_eq!(rc.classify("https://miaou.dystroy.org/3"), RefererCategory::Internal);
+        assert_eq!(rc.classify("https://notdystroy.org/"), RefererCategory::Other);
+        assert_eq!(rc.classify("https://www.google.co.uk/"), RefererCategory::Search);
+        assert_eq!(rc.classify("https://search.brave.com/search?q=a"), RefererCategory::Search);
+        assert_eq!(rc.classify("https://t.co/xyz"), RefererCategory::Social);
+        assert_eq!(rc.classify("https://old.reddit.com/r/rust"), RefererCategory::Social);
+        assert_eq!(rc.classify("https://github.com/Canop/rhit"), RefererCategory::Other);
+    }
+}
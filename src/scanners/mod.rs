@@ -0,0 +1,7 @@
+mod scan_kind;
+mod scanner_detector;
+
+pub use {
+    scan_kind::*,
+    scanner_detector::*,
+};
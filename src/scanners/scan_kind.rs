@@ -0,0 +1,31 @@
+use {
+    std::fmt,
+};
+
+/// Whether a hit comes from a vulnerability scanner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScanKind {
+    #[default]
+    Clean,
+    /// the hit matches the scan rule of the given name
+    Probe(&'static str),
+    /// the hit doesn't match any rule but its remote address
+    /// previously sent a probe
+    Scanner,
+}
+
+impl ScanKind {
+    pub fn is_scan(self) -> bool {
+        !matches!(self, Self::Clean)
+    }
+}
+
+impl fmt::Display for ScanKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Clean => write!(f, "clean"),
+            Self::Probe(rule) => write!(f, "probe ({})", rule),
+            Self::Scanner => write!(f, "from a scanner"),
+        }
+    }
+}
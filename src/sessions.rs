@@ -0,0 +1,86 @@
+use {
+    crate::*,
+    std::collections::HashMap,
+};
+
+/// A visit: the successive page hits of a client (an IP address
+/// with a user agent), with no pause longer than the inactivity
+/// timeout
+pub struct Session<'b> {
+    pub lines: Vec<&'b LogLine>, // guaranteed not empty
+}
+
+impl<'b> Session<'b> {
+    pub fn entry(&self) -> &'b LogLine {
+        self.lines[0]
+    }
+    pub fn exit(&self) -> &'b LogLine {
+        self.lines[self.lines.len() - 1]
+    }
+    pub fn pages(&self) -> usize {
+        self.lines.len()
+    }
+    /// Duration in seconds, between the first and the last hits
+    pub fn duration(&self) -> i64 {
+        self.exit().date_time.timestamp() - self.entry().date_time.timestamp()
+    }
+    /// A bounce is a session with only one page
+    pub fn is_bounce(&self) -> bool {
+        self.lines.len() == 1
+    }
+    /// Index of the day the session starts
+    pub fn date_idx(&self) -> usize {
+        self.entry().date_idx
+    }
+}
+
+/// Group the page hits (not the resources) in sessions.
+///
+/// Lines are expected to be sorted by time, and the
+/// inactivity timeout is in seconds.
+pub fn sessionize(lines: &[LogLine], timeout: i64) -> Vec<Session<'_>> {
+    let mut sessions: Vec<Session> = Vec::new();
+    // for each client, the index of its last session
+    let mut last_sessions: HashMap<u64, usize> = HashMap::new();
+    for line in lines {
+        if line.is_resource() {
+            continue;
+        }
+        let client = visitor_id(line, true);
+        let timestamp = line.date_time.timestamp();
+        if let Some(&idx) = last_sessions.get(&client) {
+            let session = &mut sessions[idx];
+            if timestamp - session.exit().date_time.timestamp() <= timeout {
+                session.lines.push(line);
+                continue;
+            }
+        }
+        last_sessions.insert(client, sessions.len());
+        sessions.push(Session { lines: vec![line] });
+    }
+    sessions
+}
+
+#[cfg(test)]
+mod sessions_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_sessionize() {
+        let lines = [
+            LogLine::test("/").with_addr("1.2.3.4").with_time("10:00:00"),
+            LogLine::test("/style.css").with_addr("1.2.3.4").with_time("10:00:01"),
+            LogLine::test("/blog").with_addr("1.2.3.5").with_time("10:01:00"),
+            LogLine::test("/about").with_addr("1.2.3.4").with_time("10:05:00"),
+            LogLine::test("/").with_addr("1.2.3.4").with_time("11:00:00"), // after the timeout
+        ];
+        let sessions = sessionize(&lines, 30 * 60);
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[0].pages(), 2);
+        assert_eq!(sessions[0].duration(), 300);
+        assert_eq!(&*sessions[0].exit().path, "/about");
+        assert!(sessions[1].is_bounce());
+        assert!(sessions[2].is_bounce());
+    }
+}
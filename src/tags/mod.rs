@@ -0,0 +1,7 @@
+mod tag_set;
+mod tagger;
+
+pub use {
+    tag_set::*,
+    tagger::*,
+};
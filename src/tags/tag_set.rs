@@ -0,0 +1,64 @@
+/// The maximal number of distinct tags
+pub const MAX_TAGS: usize = 64;
+
+/// A compact set of tag ids, stored on every log line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TagSet(u64);
+
+impl TagSet {
+    pub fn insert(&mut self, id: usize) {
+        debug_assert!(id < MAX_TAGS);
+        self.0 |= 1 << id;
+    }
+    pub fn contains(self, id: usize) -> bool {
+        id < MAX_TAGS && self.0 & (1 << id) != 0
+    }
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Iterate over the ids of the tags in the set, in increasing order
+    pub fn ids(self) -> impl Iterator<Item = usize> {
+        (0..MAX_TAGS).filter(move |&id| self.contains(id))
+    }
+    /// Return the names of the tags, separated with the separator
+    pub fn join(self, names: &[&str], separator: &str) -> String {
+        let mut s = String::new();
+        for id in self.ids() {
+            if !s.is_empty() {
+                s.push_str(separator);
+            }
+            s.push_str(names[id]);
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tag_set_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_tag_set() {
+        let mut set = TagSet::default();
+        assert!(set.is_empty());
+        set.insert(0);
+        set.insert(63);
+        assert!(set.contains(0));
+        assert!(!set.contains(1));
+        assert!(set.contains(63));
+        assert_eq!(set.ids().collect::<Vec<_>>(), vec![0, 63]);
+        let mut names = vec!["x"; MAX_TAGS];
+        names[0] = "api";
+        names[63] = "static";
+        assert_eq!(set.join(&names, ","), "api,static");
+        let mut other = TagSet::default();
+        other.insert(1);
+        assert!(!set.intersects(other));
+        other.insert(63);
+        assert!(set.intersects(other));
+    }
+}
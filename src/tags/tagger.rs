@@ -0,0 +1,242 @@
+use {
+    crate::*,
+    lazy_regex::regex::{self, Regex},
+    std::{
+        net::IpAddr,
+        path::Path,
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// The embedded tag rules
+static RULES: &str = include_str!("tag_rules.txt");
+
+#[derive(Debug, Error)]
+pub enum ParseTagRuleError {
+    #[error("invalid tag rule line {0:?}")]
+    InvalidLine(String),
+    #[error("unknown field {0:?} in tag rule")]
+    UnknownField(String),
+    #[error("invalid tag rule pattern {0:?}")]
+    InvalidRegex(#[from] regex::Error),
+    #[error("invalid IP block in tag rule: {0}")]
+    InvalidCidr(#[from] ParseCidrError),
+    #[error("invalid status in tag rule: {0}")]
+    InvalidStatus(#[from] ParseStatusFilterError),
+    #[error("too many tags, the maximum is {MAX_TAGS}")]
+    TooManyTags,
+}
+
+/// A test on a field of a log line
+enum Condition {
+    Path(Regex),
+    Ext(Vec<Box<str>>),
+    Ip(Vec<Cidr>),
+    Status(StatusFilter),
+    Method(MethodFilter),
+    Referer(Regex),
+    Agent(Regex),
+}
+
+/// A line being tagged, whose remote address is parsed at most once
+struct Candidate<'l> {
+    line: &'l LogLine,
+    addr: Option<Option<IpAddr>>,
+}
+
+impl Candidate<'_> {
+    fn addr(&mut self) -> Option<IpAddr> {
+        let line = self.line;
+        *self.addr.get_or_insert_with(|| line.remote_addr.parse().ok())
+    }
+}
+
+impl Condition {
+    fn matches(&self, candidate: &mut Candidate<'_>) -> bool {
+        let line = candidate.line;
+        match self {
+            Self::Path(regex) => regex.is_match(&line.path),
+            Self::Ext(extensions) => path_extension(&line.path)
+                .map_or(false, |ext| {
+                    extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+                }),
+            Self::Ip(cidrs) => match candidate.addr() {
+                Some(addr) => cidrs.iter().any(|cidr| cidr.contains(addr)),
+                None => false,
+            },
+            Self::Status(f) => f.accepts(line.status),
+            Self::Method(f) => f.contains(line.method),
+            Self::Referer(regex) => regex.is_match(&line.referer),
+            Self::Agent(regex) => regex.is_match(line.user_agent),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = ParseTagRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, pattern) = s
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| ParseTagRuleError::InvalidLine(s.to_owned()))?;
+        let pattern = pattern.trim();
+        Ok(match field {
+            "path" => Self::Path(Regex::new(pattern)?),
+            "ext" => Self::Ext(
+                pattern
+                    .split('|')
+                    .map(|ext| ext.trim().trim_start_matches('.').into())
+                    .collect(),
+            ),
+            "ip" => Self::Ip(
+                pattern
+                    .split(',')
+                    .map(Cidr::from_str)
+                    .collect::<Result<_, _>>()?,
+            ),
+            "status" => Self::Status(StatusFilter::from_str(pattern)?),
+            "method" => Self::Method(MethodFilter::from_string(pattern)),
+            "referer" => Self::Referer(Regex::new(pattern)?),
+            "agent" => Self::Agent(Regex::new(pattern)?),
+            _ => {
+                return Err(ParseTagRuleError::UnknownField(field.to_owned()));
+            }
+        })
+    }
+}
+
+/// Return the extension of the last segment of the path, without
+/// the query
+fn path_extension(path: &str) -> Option<&str> {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    let segment = path.rsplit('/').next().unwrap_or(path);
+    segment.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+/// A set of conditions which, when all met, give a tag to a line
+struct TagRule {
+    tag: usize,
+    conditions: Vec<(bool, Condition)>, // (negated, condition)
+}
+
+impl TagRule {
+    fn matches(&self, candidate: &mut Candidate<'_>) -> bool {
+        self.conditions
+            .iter()
+            .all(|(negated, condition)| condition.matches(candidate) != *negated)
+    }
+}
+
+/// Gives tags to log lines, according to the embedded rules and
+/// the ones of an optional file.
+pub struct Tagger {
+    names: Vec<&'static str>,
+    rules: Vec<TagRule>,
+}
+
+impl Tagger {
+    /// Build a tagger from the embedded rules, and optionally
+    /// from the ones of a file
+    pub fn new(rules_file: Option<&Path>) -> Result<Self, RhitError> {
+        let mut tagger = Self {
+            names: Vec::new(),
+            rules: Vec::new(),
+        };
+        tagger.add_rules(RULES)?;
+        if let Some(path) = rules_file {
+            let content = leak::read_leaked(path)?;
+            tagger.add_rules(content)?;
+        }
+        Ok(tagger)
+    }
+    fn add_rules(&mut self, s: &'static str) -> Result<(), ParseTagRuleError> {
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, conditions) = line
+                .split_once(':')
+                .ok_or_else(|| ParseTagRuleError::InvalidLine(line.to_owned()))?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(ParseTagRuleError::InvalidLine(line.to_owned()));
+            }
+            let conditions = conditions
+                .split(" & ")
+                .map(|c| {
+                    let c = c.trim();
+                    match c.strip_prefix('!') {
+                        Some(c) => Ok((true, c.trim_start().parse()?)),
+                        None => Ok((false, c.parse()?)),
+                    }
+                })
+                .collect::<Result<Vec<_>, ParseTagRuleError>>()?;
+            let tag = match self.id(name) {
+                Some(tag) => tag,
+                None => {
+                    if self.names.len() == MAX_TAGS {
+                        return Err(ParseTagRuleError::TooManyTags);
+                    }
+                    self.names.push(name);
+                    self.names.len() - 1
+                }
+            };
+            self.rules.push(TagRule { tag, conditions });
+        }
+        Ok(())
+    }
+    /// The names of the tags, indexed by their id
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|&n| n == name)
+    }
+    pub fn tag(&self, line: &LogLine) -> TagSet {
+        let mut tags = TagSet::default();
+        let mut candidate = Candidate { line, addr: None };
+        for rule in &self.rules {
+            if !tags.contains(rule.tag) && rule.matches(&mut candidate) {
+                tags.insert(rule.tag);
+            }
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tagger_tests {
+
+    use super::*;
+
+    fn tag_names(tagger: &Tagger, line: &LogLine) -> Vec<&'static str> {
+        tagger.tag(line).ids().map(|id| tagger.names()[id]).collect()
+    }
+
+    #[test]
+    fn test_embedded_rules() {
+        let tagger = Tagger::new(None).unwrap();
+        assert_eq!(tag_names(&tagger, &LogLine::test("/")), Vec::<&str>::new());
+        assert_eq!(tag_names(&tagger, &LogLine::test("/css/main.CSS?v=3")), vec!["static"]);
+        assert_eq!(tag_names(&tagger, &LogLine::test("/blog/feed")), vec!["feed"]);
+        assert_eq!(tag_names(&tagger, &LogLine::test("/api/logo.png")), vec!["static", "api"]);
+    }
+
+    #[test]
+    fn test_conditions() {
+        let mut tagger = Tagger::new(None).unwrap();
+        tagger.add_rules("admin: path ^/admin & ip 10.0.0.0/8, 192.168.1.1").unwrap();
+        tagger.add_rules("write: method POST,PUT,DELETE & !status 4xx,5xx").unwrap();
+        tagger.add_rules("admin: agent AdminBot").unwrap();
+        assert_eq!(tag_names(&tagger, &LogLine::test("/admin/users").with_addr("10.1.2.3")), vec!["admin"]);
+        assert_eq!(tag_names(&tagger, &LogLine::test("/admin").with_addr("192.168.1.1")), vec!["admin"]);
+        assert_eq!(tag_names(&tagger, &LogLine::test("/admin/users").with_addr("11.1.2.3")), Vec::<&str>::new());
+        let ll = LogLine::test("/api/users").with_addr("11.1.2.3").with_method("POST").with_status(201);
+        assert_eq!(tag_names(&tagger, &ll), vec!["api", "write"]);
+        let ll = LogLine::test("/users").with_addr("11.1.2.3").with_method("POST").with_status(403);
+        assert_eq!(tag_names(&tagger, &ll), Vec::<&str>::new());
+        assert!(tagger.add_rules("bad: size 3").is_err());
+        assert!(tagger.add_rules("bad path ^/").is_err());
+    }
+}
@@ -29,6 +29,10 @@ impl Time {
         }
         Ok(Self { hour, minute, second })
     }
+    /// Return the number of seconds since midnight
+    pub fn seconds(self) -> u32 {
+        self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32
+    }
 }
 
 impl FromStr for Time {
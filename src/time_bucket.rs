@@ -0,0 +1,239 @@
+use {
+    crate::*,
+    std::str::FromStr,
+    thiserror::Error,
+};
+
+/// The maximal number of bars wanted in a histogram whose
+/// bucket is automatically chosen
+const MAX_AUTO_BARS: i64 = 60;
+
+#[derive(Debug, Error)]
+pub enum ParseTimeBucketError {
+    #[error("unrecognized time bucket {0:?}, expected one of auto, 1m, 5m, 15m, 1h, 1d, 1w, 1M")]
+    Unrecognized(String),
+}
+
+/// The time slot of the bars of the dates histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBucket {
+    /// chosen according to the time span of the hits
+    Auto,
+    Minute,
+    FiveMinutes,
+    FifteenMinutes,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+/// The buckets which may be chosen by `auto`, shortest first
+static FIXED_BUCKETS: &[TimeBucket] = &[
+    TimeBucket::Minute,
+    TimeBucket::FiveMinutes,
+    TimeBucket::FifteenMinutes,
+    TimeBucket::Hour,
+    TimeBucket::Day,
+    TimeBucket::Week,
+    TimeBucket::Month,
+];
+
+impl FromStr for TimeBucket {
+    type Err = ParseTimeBucketError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `M` is for months while `m` is for minutes
+        match s.trim() {
+            "auto" | "a" => Ok(Self::Auto),
+            "1m" | "m" => Ok(Self::Minute),
+            "5m" => Ok(Self::FiveMinutes),
+            "15m" => Ok(Self::FifteenMinutes),
+            "1h" | "h" => Ok(Self::Hour),
+            "1d" | "d" => Ok(Self::Day),
+            "1w" | "w" => Ok(Self::Week),
+            "1M" | "M" => Ok(Self::Month),
+            _ => Err(ParseTimeBucketError::Unrecognized(s.to_owned())),
+        }
+    }
+}
+
+impl TimeBucket {
+    /// The name of a bucket, for column headers
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "bucket",
+            Self::Minute => "minute",
+            Self::FiveMinutes => "5 minutes",
+            Self::FifteenMinutes => "15 minutes",
+            Self::Hour => "hour",
+            Self::Day => "date",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+    /// The number of minutes of the buckets which are made of minutes
+    fn minutes(self) -> Option<i64> {
+        match self {
+            Self::Minute => Some(1),
+            Self::FiveMinutes => Some(5),
+            Self::FifteenMinutes => Some(15),
+            _ => None,
+        }
+    }
+    /// Return the index of the bucket containing the date time.
+    ///
+    /// Indexes of consecutive buckets are consecutive.
+    pub fn index(self, date_time: DateTime) -> i64 {
+        let days = date_time.date.days_since_epoch();
+        match self {
+            Self::Hour => days * 24 + date_time.time.hour as i64,
+            Self::Day => days,
+            // weeks start on monday, and 1969/12/29 was a monday
+            Self::Week => (days + 3).div_euclid(7),
+            Self::Month => date_time.date.year as i64 * 12 + date_time.date.month as i64 - 1,
+            _ => {
+                let minutes = self.minutes().unwrap_or(1);
+                let minute = days * 1440
+                    + date_time.time.hour as i64 * 60
+                    + date_time.time.minute as i64;
+                minute.div_euclid(minutes)
+            }
+        }
+    }
+    /// Return the first and last days of the bucket
+    pub fn days(self, index: i64) -> (Date, Date) {
+        match self {
+            Self::Hour => {
+                let date = Date::from_days_since_epoch(index.div_euclid(24));
+                (date, date)
+            }
+            Self::Day => {
+                let date = Date::from_days_since_epoch(index);
+                (date, date)
+            }
+            Self::Week => {
+                let monday = Date::from_days_since_epoch(index * 7 - 3);
+                (monday, monday.add_days(6))
+            }
+            Self::Month => {
+                let year = index.div_euclid(12) as u16;
+                let month = (index.rem_euclid(12) + 1) as u8;
+                let first = Date { year, month, day: 1 };
+                let next = if month == 12 {
+                    Date { year: year + 1, month: 1, day: 1 }
+                } else {
+                    Date { year, month: month + 1, day: 1 }
+                };
+                (first, next.add_days(-1))
+            }
+            _ => {
+                let minute = index * self.minutes().unwrap_or(1);
+                let date = Date::from_days_since_epoch(minute.div_euclid(1440));
+                (date, date)
+            }
+        }
+    }
+    /// Return the timestamp of the start of the bucket, and its
+    /// duration in seconds
+    pub fn span(self, index: i64) -> (i64, i64) {
+        match self {
+            Self::Hour => (index * 3600, 3600),
+            Self::Day => (index * 86400, 86400),
+            Self::Week | Self::Month => {
+                let (first, last) = self.days(index);
+                let first = first.days_since_epoch();
+                (first * 86400, (last.days_since_epoch() - first + 1) * 86400)
+            }
+            _ => {
+                let seconds = self.minutes().unwrap_or(1) * 60;
+                (index * seconds, seconds)
+            }
+        }
+    }
+    /// Return the label of the bucket. The date is omitted for
+    /// buckets shorter than a day when `with_date` is false
+    pub fn label(self, index: i64, with_date: bool) -> String {
+        let (date, _) = self.days(index);
+        let time = match self {
+            Self::Hour => format!("{:0>2}h", index.rem_euclid(24)),
+            Self::Day | Self::Week => {
+                return date.to_string();
+            }
+            Self::Month => {
+                return format!("{}/{:0>2}", date.year, date.month);
+            }
+            _ => {
+                let minute = (index * self.minutes().unwrap_or(1)).rem_euclid(1440);
+                format!("{:0>2}:{:0>2}", minute / 60, minute % 60)
+            }
+        };
+        if with_date {
+            format!("{} {}", date, time)
+        } else {
+            time
+        }
+    }
+    /// Return the bucket to use for the given time span: the
+    /// bucket itself, or the shortest one giving a readable number
+    /// of bars when it's `auto`
+    pub fn resolve(self, first: DateTime, last: DateTime) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        FIXED_BUCKETS
+            .iter()
+            .copied()
+            .find(|b| b.index(last) - b.index(first) < MAX_AUTO_BARS)
+            .unwrap_or(Self::Month)
+    }
+}
+
+#[cfg(test)]
+mod time_bucket_tests {
+
+    use super::*;
+
+    fn dt(s: &str) -> DateTime {
+        DateTime::from_nginx(s).unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let auto = TimeBucket::Auto;
+        let first = dt("21/Mar/2024:10:00:00 +0000");
+        assert_eq!(auto.resolve(first, dt("21/Mar/2024:10:40:00 +0000")), TimeBucket::Minute);
+        assert_eq!(auto.resolve(first, dt("21/Mar/2024:13:00:00 +0000")), TimeBucket::FiveMinutes);
+        assert_eq!(auto.resolve(first, dt("21/Mar/2024:23:59:00 +0000")), TimeBucket::FifteenMinutes);
+        assert_eq!(auto.resolve(first, dt("22/Mar/2024:23:59:00 +0000")), TimeBucket::Hour);
+        assert_eq!(auto.resolve(first, dt("30/Apr/2024:23:59:00 +0000")), TimeBucket::Day);
+        assert_eq!(auto.resolve(first, dt("30/Dec/2024:23:59:00 +0000")), TimeBucket::Week);
+        assert_eq!(auto.resolve(first, dt("30/Dec/2027:23:59:00 +0000")), TimeBucket::Month);
+        assert_eq!(TimeBucket::Day.resolve(first, first), TimeBucket::Day);
+    }
+
+    #[test]
+    fn test_buckets() {
+        let date_time = dt("21/Mar/2024:14:07:31 +0000"); // a thursday
+        let b = TimeBucket::FiveMinutes;
+        assert_eq!(b.label(b.index(date_time), true), "2024/03/21 14:05");
+        assert_eq!(b.label(b.index(date_time), false), "14:05");
+        let b = TimeBucket::Hour;
+        assert_eq!(b.label(b.index(date_time), false), "14h");
+        let b = TimeBucket::Week;
+        let (monday, sunday) = b.days(b.index(date_time));
+        assert_eq!(monday, Date { year: 2024, month: 3, day: 18 });
+        assert_eq!(sunday, Date { year: 2024, month: 3, day: 24 });
+        let b = TimeBucket::Month;
+        assert_eq!(b.label(b.index(date_time), true), "2024/03");
+        assert_eq!(b.days(b.index(date_time)).1, Date { year: 2024, month: 3, day: 31 });
+        assert_eq!(b.index(dt("01/Apr/2024:00:00:00 +0000")), b.index(date_time) + 1);
+        for b in FIXED_BUCKETS {
+            // a bucket contains its first second, and the next one starts after it
+            let (start, duration) = b.span(b.index(date_time));
+            assert_eq!(b.index(DateTime::from_timestamp(start)), b.index(date_time));
+            assert_eq!(b.index(DateTime::from_timestamp(start + duration)), b.index(date_time) + 1);
+        }
+        assert!("1y".parse::<TimeBucket>().is_err());
+        assert_eq!("1M".parse::<TimeBucket>().unwrap(), TimeBucket::Month);
+    }
+}
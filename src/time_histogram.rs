@@ -1,15 +1,16 @@
 use {
     crate::*,
     minimad::OwningTemplateExpander,
+    std::collections::HashSet,
     termimad::*,
 };
 
 static MD: &str = r#"
-|:-:|:-:|:-:|:-
-|**hour**|**hits**|**bytes**|**${scale}**
-|:-|:-:|-:|:-
+|:-:|:-:|:-:|:-:|:-
+|**hour**|**hits**|**visitors**|**bytes**|**${scale}**
+|:-|:-:|-:|-:|:-
 ${bars
-|${hour}|${hits}|${bytes}|*${bar}*
+|${hour}|${hits}|${visitors}|${bytes}|*${bar}*
 }
 |-:
 "#;
@@ -19,6 +20,7 @@ struct Bar {
     pub hour: u8,
     pub hits: u64,
     pub bytes_sent: u64,
+    pub visitors: u64,
 }
 
 impl Bar {
@@ -27,6 +29,7 @@ impl Bar {
             hour,
             hits: 0,
             bytes_sent: 0,
+            visitors: 0,
         }
     }
 }
@@ -45,10 +48,14 @@ impl TimeHistogram {
         let mut bars: Vec<Bar> = (0..24)
             .map(Bar::new)
             .collect();
+        let mut hour_visitors = vec![HashSet::new(); 24];
         for line in &base.lines {
             let idx = line.time().hour as usize;
             bars[idx].hits += 1;
             bars[idx].bytes_sent += line.bytes_sent;
+            if base.visitors && hour_visitors[idx].insert(line.visitor) {
+                bars[idx].visitors += 1;
+            }
         }
         Self { bars }
     }
@@ -60,7 +67,7 @@ impl TimeHistogram {
         let mut expander = OwningTemplateExpander::new();
         let max_bar = self.bars
             .iter()
-            .map(|b| if printer.key==Key::Hits { b.hits } else { b.bytes_sent })
+            .map(|b| printer.key.pick(b.hits, b.bytes_sent, b.visitors))
             .max().unwrap();
         expander.set(
             "scale",
@@ -68,11 +75,12 @@ impl TimeHistogram {
         );
         let max_bar = max_bar as f32;
         for bar in &self.bars {
-            let value = if printer.key == Key::Hits { bar.hits } else { bar.bytes_sent };
+            let value = printer.key.pick(bar.hits, bar.bytes_sent, bar.visitors);
             let part = (value as f32) / max_bar;
             expander.sub("bars")
                 .set("hour", bar.hour)
                 .set_md("hits", printer.md_hits(bar.hits as usize))
+                .set_md("visitors", printer.md_visitors(bar.visitors as usize))
                 .set_md("bytes", printer.md_bytes(bar.bytes_sent))
                 .set("bar", ProgressBar::new(part, 20));
         }
@@ -1,7 +1,11 @@
 use {
+    crate::*,
     std::cmp::Ordering,
 };
 
+/// Max number of chars of the histogram of a trend
+const MAX_HISTO_WIDTH: usize = 20;
+
 #[derive(Debug, Clone, Eq)]
 pub struct Trend {
     pub sum_per_day: Vec<u64>,
@@ -17,6 +21,16 @@ impl Trend {
     pub fn sum(&self) -> u64 {
         self.ref_count + self.tail_count
     }
+    /// Return the histogram of the trend, adjacent buckets being
+    /// merged when they're too many
+    pub fn histo_line(&self) -> String {
+        if self.sum_per_day.len() <= MAX_HISTO_WIDTH {
+            return histo_line(&self.sum_per_day, self.max_day_count(), false);
+        }
+        let counts = merge_buckets(&self.sum_per_day);
+        let max = counts.iter().copied().max().unwrap_or(0);
+        histo_line(&counts, max, false)
+    }
     pub fn markdown(&self) -> &'static str {
         if self.value > 200 {
             if self.value > 900 {
@@ -40,6 +54,18 @@ impl Trend {
     }
 }
 
+/// Merge adjacent buckets so that there are at most `MAX_HISTO_WIDTH`
+/// of them. Chunks are aligned on the end, so that the last one is
+/// the tail, the first one being possibly smaller
+fn merge_buckets(counts: &[u64]) -> Vec<u64> {
+    let chunk_size = (counts.len() + MAX_HISTO_WIDTH - 1) / MAX_HISTO_WIDTH;
+    counts
+        .rchunks(chunk_size.max(1))
+        .rev()
+        .map(|chunk| chunk.iter().sum())
+        .collect()
+}
+
 impl Ord for Trend {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.value < other.value {
@@ -66,3 +92,41 @@ impl PartialEq for Trend {
     }
 }
 
+#[cfg(test)]
+mod trend_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_merge_buckets() {
+        let counts: Vec<u64> = (1..=20).collect();
+        assert_eq!(merge_buckets(&counts), counts);
+        // 21 buckets: pairs aligned on the end, the first one alone
+        let counts: Vec<u64> = (1..=21).collect();
+        let merged = merge_buckets(&counts);
+        assert_eq!(merged.len(), 11);
+        assert_eq!(merged[0], 1);
+        assert_eq!(merged[1], 2 + 3);
+        assert_eq!(merged[10], 20 + 21);
+        // 41 buckets: chunks of 3, the first one of 2
+        let counts: Vec<u64> = (1..=41).collect();
+        let merged = merge_buckets(&counts);
+        assert_eq!(merged.len(), 14);
+        assert_eq!(merged[0], 1 + 2);
+        assert_eq!(merged[13], 39 + 40 + 41);
+        assert_eq!(merged.iter().sum::<u64>(), counts.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn test_histo_line_width() {
+        let trend = |n: u64| Trend {
+            sum_per_day: (0..n).collect(),
+            value: 0,
+            ref_count: 0,
+            tail_count: 0,
+        };
+        assert_eq!(trend(20).histo_line().chars().count(), 20);
+        assert_eq!(trend(21).histo_line().chars().count(), 11);
+        assert_eq!(trend(24 * 20).histo_line().chars().count(), 20);
+    }
+}
@@ -1,14 +1,14 @@
 use {
     crate::*,
+    std::collections::HashSet,
 };
 
-const MAX_HISTO_LEN: usize = 20;
-
 pub struct TrendComputer {
-    histo_offset: usize, // number of old days skipped
+    histo_offset: usize, // number of old buckets skipped
     histo_len: usize, // = ref_len + tail_len
     pub ref_len: usize,
     pub tail_len: usize,
+    pub bucket: TrendBucket,
     normalization_factor: f32,
     pub key: Key,
 }
@@ -17,13 +17,14 @@ impl TrendComputer {
         base: &LogBase,
         args: &args::Args,
     ) -> Result<Option<Self>, RhitError> {
-        let dc = if let Some(date_filter) = base.filterer.date_filter() {
+        let windows = TrendWindows::new(args);
+        let dc = if base.filterer.has_date_filter() {
             // if there's a date filtering, we don't want the
             // histograms and trend computation to be based
             // on an excluded tail, so we determine the end.
             let mut dc = 0;
             for (idx, date) in base.dates.iter().enumerate() {
-                if date_filter.overlaps(*date) {
+                if base.filterer.overlaps(*date) {
                     dc = idx + 1;
                 }
             }
@@ -31,40 +32,60 @@ impl TrendComputer {
         } else {
             base.day_count()
         };
-        if dc < 4 {
+        let counts_per_bucket: Vec<u64> = match windows.bucket {
+            TrendBucket::Day => base
+                .unfiltered_histogram
+                .bars
+                .iter()
+                .map(|b| b.hits)
+                .collect(),
+            TrendBucket::Hour => base.unfiltered_hourly_hits.clone(),
+        };
+        let mut bc = dc * windows.bucket.per_day();
+        // the hours after the last hit aren't a drop
+        while bc > 0 && counts_per_bucket[bc - 1] == 0 {
+            bc -= 1;
+        }
+        if bc < windows.tail + 2 {
             return Ok(None);
         }
-        let histo_len = dc.min(MAX_HISTO_LEN);
-        let histo_offset = dc - histo_len;
-        let tail_len = 2;
-        let ref_len = histo_len - tail_len;
+        let tail_len = windows.tail;
+        let ref_len = windows.reference.min(bc - tail_len);
+        let histo_len = ref_len + tail_len;
+        let histo_offset = bc - histo_len;
         let mut computer = Self {
             tail_len,
             ref_len,
             histo_len,
             histo_offset,
+            bucket: windows.bucket,
             normalization_factor: 1f32, // temporary value
             key: args.key,
         };
-        let counts_per_day: Vec<u64> = base
-            .unfiltered_histogram
-            .bars
-            .iter()
-            .map(|b| b.hits)
-            .collect();
-        let (ref_count, tail_count) = computer.compute_ref_tail_counts(&counts_per_day);
+        let (ref_count, tail_count) = computer.compute_ref_tail_counts(
+            &counts_per_bucket[histo_offset..bc]
+        );
         computer.normalization_factor = (ref_count as f32) / (tail_count as f32);
         Ok(Some(computer))
     }
+    /// Return the index of the bucket of the line, not taking
+    /// the offset into account
+    fn bucket_idx<DI: DateIndexed>(&self, line: &DI) -> usize {
+        match self.bucket {
+            TrendBucket::Day => line.date_idx(),
+            TrendBucket::Hour => line.date_idx() * 24 + line.hour() as usize,
+        }
+    }
     pub fn compute_histo_line<DI: DateIndexed>(&self, lines: &[DI]) -> Vec<u64> {
         let mut counts = vec![0; self.histo_len];
         match self.key {
             Key::Hits => {
                 for line in lines {
-                    if line.date_idx() < self.histo_offset {
+                    let idx = self.bucket_idx(line);
+                    if idx < self.histo_offset {
                         continue;
                     }
-                    let idx = line.date_idx() - self.histo_offset;
+                    let idx = idx - self.histo_offset;
                     if idx >= self.histo_len {
                         break;
                     }
@@ -73,24 +94,52 @@ impl TrendComputer {
             }
             Key::Bytes => {
                 for line in lines {
-                    if line.date_idx() < self.histo_offset {
+                    let idx = self.bucket_idx(line);
+                    if idx < self.histo_offset {
+                        continue;
+                    }
+                    let idx = idx - self.histo_offset;
+                    if idx >= self.histo_len {
+                        break;
+                    }
+                    counts[idx] += line.bytes();
+                }
+            }
+            Key::Visitors => {
+                // lines are sorted by date, so we only need the visitors
+                // of the current bucket
+                let mut bucket_visitors = HashSet::new();
+                let mut bucket_idx = None;
+                for line in lines {
+                    let idx = self.bucket_idx(line);
+                    if idx < self.histo_offset {
                         continue;
                     }
-                    counts[line.date_idx() - self.histo_offset] += line.bytes();
+                    let idx = idx - self.histo_offset;
+                    if idx >= self.histo_len {
+                        break;
+                    }
+                    if bucket_idx != Some(idx) {
+                        bucket_visitors.clear();
+                        bucket_idx = Some(idx);
+                    }
+                    if bucket_visitors.insert(line.visitor()) {
+                        counts[idx] += 1;
+                    }
                 }
             }
         }
         counts
     }
-    fn compute_ref_tail_counts(&self, counts_per_day: &[u64]) -> (u64, u64) {
+    fn compute_ref_tail_counts(&self, counts_per_bucket: &[u64]) -> (u64, u64) {
         let (mut ref_count, mut tail_count) = (0, 0);
         let mut idx = 0;
         while idx < self.ref_len {
-            ref_count += counts_per_day[idx];
+            ref_count += counts_per_bucket[idx];
             idx += 1;
         }
         while idx < self.histo_len {
-            tail_count += counts_per_day[idx];
+            tail_count += counts_per_bucket[idx];
             idx += 1;
         }
         (ref_count, tail_count)
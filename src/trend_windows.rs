@@ -0,0 +1,76 @@
+use {
+    crate::*,
+    clap::ValueEnum,
+    serde::Deserialize,
+};
+
+/// Number of buckets of the tail, by default
+const DEFAULT_TAIL_LEN: usize = 2;
+
+/// Number of buckets of the reference, by default
+const DEFAULT_REF_LEN: usize = 18;
+
+/// The time slot in which hits are counted for histograms and trends
+#[derive(ValueEnum, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    #[default]
+    Day,
+    Hour,
+}
+
+impl TrendBucket {
+    pub fn per_day(self) -> usize {
+        match self {
+            Self::Day => 1,
+            Self::Hour => 24,
+        }
+    }
+    /// The name of the buckets, for column headers
+    pub fn plural_name(self) -> &'static str {
+        match self {
+            Self::Day => "days",
+            Self::Hour => "hours",
+        }
+    }
+}
+
+/// The trends part of the configuration, overridden by
+/// the launch arguments
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrendsConfig {
+    /// number of buckets to which the tail is compared
+    pub reference: Option<usize>,
+    /// number of the most recent buckets
+    pub tail: Option<usize>,
+    pub bucket: Option<TrendBucket>,
+}
+
+/// The periods compared in trends: the last `tail` buckets
+/// against the `reference` ones before them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrendWindows {
+    pub reference: usize,
+    pub tail: usize,
+    pub bucket: TrendBucket,
+}
+
+impl TrendWindows {
+    pub fn new(args: &args::Args) -> Self {
+        let config = &args.config.trends;
+        let reference = args.trend_ref
+            .or(config.reference)
+            .unwrap_or(DEFAULT_REF_LEN)
+            .max(1);
+        let tail = args.trend_tail
+            .or(config.tail)
+            .unwrap_or(DEFAULT_TAIL_LEN)
+            .max(1);
+        let bucket = args.trend_bucket
+            .or(config.bucket)
+            .unwrap_or_default();
+        Self { reference, tail, bucket }
+    }
+}
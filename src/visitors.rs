@@ -0,0 +1,54 @@
+use {
+    crate::*,
+    std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        hash::{Hash, Hasher},
+    },
+};
+
+/// Compute the identifier of the visitor of a hit, which is a
+/// hash of its remote address and, optionally, of its user agent
+pub fn visitor_id(line: &LogLine, with_user_agent: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.remote_addr.hash(&mut hasher);
+    if with_user_agent {
+        line.user_agent.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Count the distinct visitors of a set of hits
+pub fn count_visitors<'b, I>(lines: I) -> usize
+where
+    I: IntoIterator<Item = &'b LogLine>,
+{
+    lines
+        .into_iter()
+        .map(|line| line.visitor)
+        .collect::<HashSet<u64>>()
+        .len()
+}
+
+#[cfg(test)]
+mod visitors_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_count_visitors() {
+        let mut lines = vec![
+            LogLine::test("/").with_addr("1.2.3.4").with_user_agent("curl"),
+            LogLine::test("/").with_addr("1.2.3.4").with_user_agent("Firefox"),
+            LogLine::test("/").with_addr("1.2.3.5").with_user_agent("Firefox"),
+            LogLine::test("/").with_addr("1.2.3.4").with_user_agent("curl"),
+        ];
+        for line in &mut lines {
+            line.visitor = visitor_id(line, false);
+        }
+        assert_eq!(count_visitors(&lines), 2);
+        for line in &mut lines {
+            line.visitor = visitor_id(line, true);
+        }
+        assert_eq!(count_visitors(&lines), 3);
+    }
+}